@@ -0,0 +1,62 @@
+-- Pictures and videos that failed processing are "quarantined" so that the user
+-- can review them and either retry processing or remove them. A file that can't
+-- be decoded is also marked as broken so it is excluded from display and is not
+-- reprocessed on every launch. Reading metadata might only fail for a while, such
+-- as when a network share drops out, so those files are still shown and retried.
+
+CREATE TABLE pictures_quarantine (
+        picture_id     INTEGER PRIMARY KEY UNIQUE NOT NULL, -- unique ID for picture
+        stage          TEXT NOT NULL, -- processing stage that failed. 'Metadata', 'Thumbnail', or 'MotionPhoto'.
+        error          TEXT NOT NULL, -- error message from failure
+        quarantined_ts DATETIME NOT NULL, -- UTC timestamp of failure
+
+        FOREIGN KEY (picture_id) REFERENCES pictures (picture_id) ON DELETE CASCADE
+);
+
+CREATE TABLE videos_quarantine (
+        video_id       INTEGER PRIMARY KEY UNIQUE NOT NULL, -- unique ID for video
+        stage          TEXT NOT NULL, -- processing stage that failed. 'Metadata' or 'Thumbnail'.
+        error          TEXT NOT NULL, -- error message from failure
+        quarantined_ts DATETIME NOT NULL, -- UTC timestamp of failure
+
+        FOREIGN KEY (video_id) REFERENCES videos (video_id) ON DELETE CASCADE
+);
+
+-- Foreign keys aren't enabled on the connection, so explicitly remove quarantine
+-- rows of deleted pictures and videos. Otherwise a new picture or video that reuses
+-- the ID of a deleted one would be quarantined.
+
+CREATE TRIGGER pictures_quarantine_cleanup AFTER DELETE ON pictures BEGIN
+  DELETE FROM pictures_quarantine WHERE picture_id = old.picture_id;
+END;
+
+CREATE TRIGGER videos_quarantine_cleanup AFTER DELETE ON videos BEGIN
+  DELETE FROM videos_quarantine WHERE video_id = old.video_id;
+END;
+
+-- All quarantined pictures and videos.
+
+CREATE VIEW quarantine AS
+
+SELECT
+  picture_id,
+  NULL AS video_id,
+  pictures.picture_path_b64 AS path_b64,
+  stage,
+  error,
+  quarantined_ts
+FROM pictures_quarantine
+INNER JOIN pictures USING (picture_id)
+
+UNION ALL
+
+SELECT
+  NULL AS picture_id,
+  video_id,
+  videos.video_path_b64 AS path_b64,
+  stage,
+  error,
+  quarantined_ts
+FROM videos_quarantine
+INNER JOIN videos USING (video_id)
+;
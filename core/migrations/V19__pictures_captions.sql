@@ -0,0 +1,45 @@
+-- User-entered title, caption, and description for a picture.
+
+CREATE TABLE pictures_captions (
+        picture_id     INTEGER PRIMARY KEY UNIQUE NOT NULL, -- unique ID for picture
+        title          TEXT, -- short title
+        caption        TEXT, -- one line caption
+        description    TEXT, -- longer free-form description
+
+        FOREIGN KEY (picture_id) REFERENCES pictures (picture_id) ON DELETE CASCADE
+);
+
+-- Full text search index over captions.
+-- External content table, so kept in sync with triggers.
+
+CREATE VIRTUAL TABLE pictures_captions_fts USING fts5(
+        title,
+        caption,
+        description,
+        content='pictures_captions',
+        content_rowid='picture_id'
+);
+
+CREATE TRIGGER pictures_captions_ai AFTER INSERT ON pictures_captions BEGIN
+  INSERT INTO pictures_captions_fts (rowid, title, caption, description)
+  VALUES (new.picture_id, new.title, new.caption, new.description);
+END;
+
+CREATE TRIGGER pictures_captions_ad AFTER DELETE ON pictures_captions BEGIN
+  INSERT INTO pictures_captions_fts (pictures_captions_fts, rowid, title, caption, description)
+  VALUES ('delete', old.picture_id, old.title, old.caption, old.description);
+END;
+
+CREATE TRIGGER pictures_captions_au AFTER UPDATE ON pictures_captions BEGIN
+  INSERT INTO pictures_captions_fts (pictures_captions_fts, rowid, title, caption, description)
+  VALUES ('delete', old.picture_id, old.title, old.caption, old.description);
+  INSERT INTO pictures_captions_fts (rowid, title, caption, description)
+  VALUES (new.picture_id, new.title, new.caption, new.description);
+END;
+
+-- Foreign keys aren't enabled on the connection, so explicitly remove captions
+-- of deleted pictures. Otherwise a new picture that reuses the ID of a deleted
+-- one would show the deleted picture's caption.
+CREATE TRIGGER pictures_captions_cleanup AFTER DELETE ON pictures BEGIN
+  DELETE FROM pictures_captions WHERE picture_id = old.picture_id;
+END;
@@ -0,0 +1,13 @@
+-- Trips and events. Runs of photos and videos grouped by time and place.
+-- Items aren't linked to events. An item is part of an event if its ordering
+-- timestamp is between the start and end timestamps of the event.
+
+CREATE TABLE events (
+        event_id       INTEGER PRIMARY KEY UNIQUE NOT NULL, -- unique ID for event
+        start_ts       DATETIME NOT NULL, -- timestamp of first item in event
+        end_ts         DATETIME NOT NULL, -- timestamp of last item in event
+        place          TEXT, -- name of place guessed from folder names
+        name           TEXT -- name given by user, overrides automatic title
+);
+
+CREATE INDEX events_start_ts_idx ON events (start_ts);
@@ -0,0 +1,12 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// TODO include captions in exports and web galleries. Fotema has neither yet,
+// so that part of the captions feature is still to do.
+
+pub mod model;
+pub mod repo;
+
+pub use model::Caption;
+pub use repo::Repository;
@@ -0,0 +1,53 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+/// User-entered text describing a picture.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Caption {
+    /// Short title.
+    pub title: Option<String>,
+
+    /// One line caption.
+    pub caption: Option<String>,
+
+    /// Longer free-form description.
+    pub description: Option<String>,
+}
+
+impl Caption {
+    /// Builds a caption, treating blank text as absent.
+    pub fn new(title: &str, caption: &str, description: &str) -> Self {
+        fn non_blank(s: &str) -> Option<String> {
+            let s = s.trim();
+            (!s.is_empty()).then(|| s.to_string())
+        }
+
+        Caption {
+            title: non_blank(title),
+            caption: non_blank(caption),
+            description: non_blank(description),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.caption.is_none() && self.description.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blank_is_empty() {
+        assert!(Caption::new("", "  ", "\n").is_empty());
+    }
+
+    #[test]
+    fn test_trims() {
+        let caption = Caption::new(" Beach ", "", "");
+        assert_eq!(Some("Beach".to_string()), caption.title);
+        assert!(!caption.is_empty());
+    }
+}
@@ -0,0 +1,172 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::model::Caption;
+use crate::PictureId;
+
+use anyhow::*;
+use rusqlite;
+use rusqlite::params;
+use rusqlite::OptionalExtension;
+use rusqlite::Row;
+use std::result::Result::Ok;
+use std::sync::{Arc, Mutex};
+
+/// Repository of picture titles, captions, and descriptions.
+/// Repository is backed by a Sqlite database.
+#[derive(Debug, Clone)]
+pub struct Repository {
+    /// Connection to backing Sqlite database.
+    con: Arc<Mutex<rusqlite::Connection>>,
+}
+
+impl Repository {
+    pub fn open(con: Arc<Mutex<rusqlite::Connection>>) -> Result<Repository> {
+        Ok(Repository { con })
+    }
+
+    /// Gets caption for a picture. Caption will be empty if the user hasn't entered one.
+    pub fn get(&self, picture_id: PictureId) -> Result<Caption> {
+        let con = self.con.lock().unwrap();
+        let mut stmt = con.prepare(
+            "SELECT
+                title,
+                caption,
+                description
+            FROM pictures_captions
+            WHERE picture_id = ?1",
+        )?;
+
+        let caption = stmt
+            .query_row([picture_id.id()], |row| self.to_caption(row))
+            .optional()?
+            .unwrap_or_default();
+
+        Ok(caption)
+    }
+
+    /// Sets caption for a picture. An empty caption is deleted.
+    pub fn set(&mut self, picture_id: PictureId, caption: &Caption) -> Result<()> {
+        let con = self.con.lock().unwrap();
+
+        if caption.is_empty() {
+            let mut stmt = con.prepare("DELETE FROM pictures_captions WHERE picture_id = ?1")?;
+            stmt.execute([picture_id.id()])?;
+            return Ok(());
+        }
+
+        let mut stmt = con.prepare(
+            "INSERT INTO pictures_captions (
+                picture_id,
+                title,
+                caption,
+                description
+            ) VALUES (
+                ?1, ?2, ?3, ?4
+            ) ON CONFLICT (picture_id) DO UPDATE SET
+                title = ?2,
+                caption = ?3,
+                description = ?4
+            ",
+        )?;
+
+        stmt.execute(params![
+            picture_id.id(),
+            caption.title,
+            caption.caption,
+            caption.description,
+        ])?;
+
+        Ok(())
+    }
+
+    /// Finds pictures with captions matching all words of a query.
+    /// Words match as prefixes, so "beac" will find "beach". Best matches first.
+    pub fn search(&self, query: &str) -> Result<Vec<PictureId>> {
+        let Some(query) = to_fts_query(query) else {
+            return Ok(vec![]);
+        };
+
+        let con = self.con.lock().unwrap();
+        let mut stmt = con.prepare(
+            "SELECT rowid AS picture_id
+            FROM pictures_captions_fts
+            WHERE pictures_captions_fts MATCH ?1
+            ORDER BY rank",
+        )?;
+
+        let result = stmt
+            .query_map([query], |row| row.get("picture_id").map(PictureId::new))?
+            .flatten()
+            .collect();
+
+        Ok(result)
+    }
+
+    fn to_caption(&self, row: &Row<'_>) -> rusqlite::Result<Caption> {
+        Ok(Caption {
+            title: row.get("title")?,
+            caption: row.get("caption")?,
+            description: row.get("description")?,
+        })
+    }
+}
+
+/// Converts user input to an FTS5 query. Each word is quoted so punctuation
+/// isn't interpreted as FTS5 syntax.
+fn to_fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"*", term.replace('"', "\"\"")))
+        .collect();
+
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database;
+
+    #[test]
+    fn test_to_fts_query() {
+        assert_eq!(None, to_fts_query("  "));
+        assert_eq!(
+            Some("\"red\"* \"car\"*".to_string()),
+            to_fts_query("red car")
+        );
+        assert_eq!(
+            Some("\"say \"\"hi\"*".to_string()),
+            to_fts_query("say \"hi")
+        );
+    }
+
+    #[test]
+    fn test_search() {
+        let con = database::setup_in_memory().unwrap();
+        con.execute(
+            "INSERT INTO pictures (
+                picture_id,
+                picture_path_b64,
+                picture_path_lossy,
+                link_path_b64,
+                link_path_lossy
+            ) VALUES (1, 'YS5qcGc=', 'a.jpg', 'YQ==', 'a')",
+            [],
+        )
+        .unwrap();
+
+        let mut repo = Repository::open(Arc::new(Mutex::new(con))).unwrap();
+        let picture_id = PictureId::new(1);
+
+        repo.set(picture_id, &Caption::new("Holiday", "At the beach", ""))
+            .unwrap();
+        assert_eq!(vec![picture_id], repo.search("beac").unwrap());
+        assert!(repo.search("mountain").unwrap().is_empty());
+
+        repo.set(picture_id, &Caption::default()).unwrap();
+        assert!(repo.search("beach").unwrap().is_empty());
+        assert!(repo.get(picture_id).unwrap().is_empty());
+    }
+}
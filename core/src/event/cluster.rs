@@ -0,0 +1,184 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::Visual;
+use chrono::{DateTime, TimeDelta, Utc};
+use h3o::LatLng;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A gap between items longer than this starts a new event.
+const MAX_TIME_GAP_HOURS: i64 = 24;
+
+/// Moving further than this between items starts a new event, such as when
+/// flying out on a trip.
+const MAX_DISTANCE_KM: f64 = 100.0;
+
+/// Fewer items than this aren't worth calling an event.
+const MIN_EVENT_SIZE: usize = 5;
+
+/// Folder names that say nothing about where a picture was taken.
+const GENERIC_FOLDER_NAMES: [&str; 6] = [
+    "camera",
+    "dcim",
+    "pictures",
+    "photos",
+    "videos",
+    "screenshots",
+];
+
+/// A run of items that should be grouped into an event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cluster {
+    pub start_ts: DateTime<Utc>,
+    pub end_ts: DateTime<Utc>,
+    pub place: Option<String>,
+}
+
+/// Group photos and videos into events by splitting at long gaps in time
+/// or at big jumps in location.
+pub fn cluster(visuals: &[Arc<Visual>]) -> Vec<Cluster> {
+    let mut visuals: Vec<&Arc<Visual>> = visuals.iter().collect();
+    visuals.sort_by_key(|v| v.ordering_ts);
+
+    let max_gap = TimeDelta::hours(MAX_TIME_GAP_HOURS);
+
+    let mut groups: Vec<Vec<&Arc<Visual>>> = vec![];
+    let mut last_location: Option<LatLng> = None;
+
+    for visual in visuals {
+        let is_new_event = match groups.last().and_then(|g| g.last()) {
+            None => true,
+            Some(previous) => {
+                let is_long_gap = visual.ordering_ts - previous.ordering_ts > max_gap;
+                let is_far_away = visual
+                    .location
+                    .zip(last_location)
+                    .is_some_and(|(here, there)| here.distance_km(there) > MAX_DISTANCE_KM);
+                is_long_gap || is_far_away
+            }
+        };
+
+        if is_new_event {
+            groups.push(vec![]);
+            last_location = None;
+        }
+
+        if let Some(group) = groups.last_mut() {
+            group.push(visual);
+        }
+
+        if visual.location.is_some() {
+            last_location = visual.location;
+        }
+    }
+
+    groups
+        .into_iter()
+        .filter(|group| group.len() >= MIN_EVENT_SIZE)
+        .map(|group| Cluster {
+            start_ts: group[0].ordering_ts,
+            end_ts: group[group.len() - 1].ordering_ts,
+            place: place_name(&group),
+        })
+        .collect()
+}
+
+/// Most common meaningful folder name in a group. Folders like "Lisbon 2023" are
+/// a good hint for where a trip was, but folders like "DCIM" or "2023/03" aren't.
+fn place_name(group: &[&Arc<Visual>]) -> Option<String> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    group
+        .iter()
+        .filter_map(|v| v.folder_name())
+        .filter(|name| name.chars().any(|c| c.is_alphabetic()))
+        .filter(|name| !GENERIC_FOLDER_NAMES.contains(&name.to_lowercase().as_str()))
+        .for_each(|name| *counts.entry(name).or_default() += 1);
+
+    counts
+        .into_iter()
+        .max_by(|(name1, count1), (name2, count2)| count1.cmp(count2).then(name2.cmp(name1)))
+        .map(|(name, _)| name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PictureId, VisualId};
+    use std::path::PathBuf;
+
+    fn visual(
+        id: i64,
+        folder: &str,
+        ordering_ts: &str,
+        location: Option<(f64, f64)>,
+    ) -> Arc<Visual> {
+        Arc::new(Visual {
+            visual_id: VisualId::new(format!("{}", id)),
+            parent_path: PathBuf::from("/pics").join(folder),
+            thumbnail_path: None,
+            video_id: None,
+            video_path: None,
+            video_transcoded_path: None,
+            video_duration: None,
+            video_orientation: None,
+            picture_id: Some(PictureId::new(id)),
+            picture_path: Some(PathBuf::from(format!("/pics/{}/{}.jpg", folder, id))),
+            picture_orientation: None,
+            motion_photo_video_path: None,
+            ordering_ts: ordering_ts.parse::<DateTime<Utc>>().unwrap(),
+            is_selfie: None,
+            is_live_photo: false,
+            is_transcode_required: None,
+            location: location.map(|(lat, lng)| LatLng::new(lat, lng).unwrap()),
+        })
+    }
+
+    #[test]
+    fn test_cluster_splits_on_time_gap() {
+        let visuals: Vec<Arc<Visual>> = (0..5)
+            .map(|i| visual(i, "Lisbon", &format!("2023-03-0{}T10:00:00Z", i + 1), None))
+            .chain((5..10).map(|i| {
+                visual(
+                    i,
+                    "Birthday",
+                    &format!("2023-04-0{}T10:00:00Z", i - 4),
+                    None,
+                )
+            }))
+            .collect();
+
+        let clusters = cluster(&visuals);
+        assert_eq!(2, clusters.len());
+        assert_eq!(Some("Lisbon".to_string()), clusters[0].place);
+        assert_eq!(visuals[4].ordering_ts, clusters[0].end_ts);
+        assert_eq!(Some("Birthday".to_string()), clusters[1].place);
+    }
+
+    #[test]
+    fn test_cluster_splits_on_distance() {
+        let dublin = Some((53.35, -6.26));
+        let lisbon = Some((38.72, -9.14));
+        let visuals: Vec<Arc<Visual>> = (0..5)
+            .map(|i| visual(i, "DCIM", &format!("2023-03-01T0{}:00:00Z", i), dublin))
+            .chain(
+                (5..10).map(|i| visual(i, "DCIM", &format!("2023-03-01T{}:00:00Z", i + 5), lisbon)),
+            )
+            .collect();
+
+        let clusters = cluster(&visuals);
+        assert_eq!(2, clusters.len());
+        assert_eq!(None, clusters[0].place);
+    }
+
+    #[test]
+    fn test_cluster_skips_small_groups() {
+        let visuals = vec![
+            visual(1, "2023", "2023-03-01T10:00:00Z", None),
+            visual(2, "2023", "2023-03-01T11:00:00Z", None),
+        ];
+        assert!(cluster(&visuals).is_empty());
+    }
+}
@@ -0,0 +1,12 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+pub mod cluster;
+pub mod model;
+pub mod repo;
+
+pub use cluster::Cluster;
+pub use model::Event;
+pub use model::EventId;
+pub use repo::Repository;
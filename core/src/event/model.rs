@@ -0,0 +1,52 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use chrono::{DateTime, Utc};
+use std::fmt::Display;
+
+/// Database ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventId(i64);
+
+impl EventId {
+    pub fn new(id: i64) -> Self {
+        Self(id)
+    }
+
+    /// FIXME replace this with a To/From SQL implementation.
+    pub fn id(&self) -> i64 {
+        self.0
+    }
+}
+
+impl Display for EventId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A trip or event. A run of photos and videos taken close together in time and place.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub event_id: EventId,
+
+    /// Timestamp of first item in event.
+    pub start_ts: DateTime<Utc>,
+
+    /// Timestamp of last item in event.
+    pub end_ts: DateTime<Utc>,
+
+    /// Name of place, guessed from folder names. Used for the automatic title.
+    pub place: Option<String>,
+
+    /// Name given by the user. Overrides the automatic title.
+    pub name: Option<String>,
+}
+
+impl Event {
+    /// Is an item taken at a timestamp part of this event?
+    pub fn contains(&self, ts: DateTime<Utc>) -> bool {
+        self.start_ts <= ts && ts <= self.end_ts
+    }
+}
@@ -0,0 +1,178 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::cluster::Cluster;
+use super::model::{Event, EventId};
+
+use anyhow::*;
+use rusqlite;
+use rusqlite::params;
+use rusqlite::Row;
+use std::result::Result::Ok;
+use std::sync::{Arc, Mutex};
+
+/// Repository of trips and events.
+/// Repository is backed by a Sqlite database.
+#[derive(Debug, Clone)]
+pub struct Repository {
+    /// Connection to backing Sqlite database.
+    con: Arc<Mutex<rusqlite::Connection>>,
+}
+
+impl Repository {
+    pub fn open(con: Arc<Mutex<rusqlite::Connection>>) -> Result<Repository> {
+        Ok(Repository { con })
+    }
+
+    /// All events, oldest first.
+    pub fn all(&self) -> Result<Vec<Event>> {
+        let con = self.con.lock().unwrap();
+        let mut stmt = con.prepare(
+            "SELECT
+                event_id,
+                start_ts,
+                end_ts,
+                place,
+                name
+            FROM events
+            ORDER BY start_ts ASC",
+        )?;
+
+        let result = stmt
+            .query_map([], |row| self.to_event(row))?
+            .flatten()
+            .collect();
+
+        Ok(result)
+    }
+
+    /// Replaces events with freshly computed clusters.
+    /// A cluster that overlaps an existing event keeps that event's ID and
+    /// user-given name, so renamed events survive new items being added.
+    pub fn update(&mut self, clusters: &[Cluster]) -> Result<()> {
+        let existing = self.all()?;
+        let mut unmatched: Vec<&Event> = existing.iter().collect();
+
+        let mut con = self.con.lock().unwrap();
+        let tx = con.transaction()?;
+
+        {
+            let mut update_stmt = tx.prepare(
+                "UPDATE events SET
+                    start_ts = ?2,
+                    end_ts = ?3,
+                    place = ?4
+                WHERE event_id = ?1",
+            )?;
+
+            let mut insert_stmt = tx.prepare(
+                "INSERT INTO events (
+                    start_ts,
+                    end_ts,
+                    place
+                ) VALUES (
+                    ?1, ?2, ?3
+                )",
+            )?;
+
+            let mut delete_stmt = tx.prepare("DELETE FROM events WHERE event_id = ?1")?;
+
+            for cluster in clusters {
+                let overlapping = unmatched
+                    .iter()
+                    .position(|e| e.start_ts <= cluster.end_ts && cluster.start_ts <= e.end_ts);
+
+                if let Some(index) = overlapping {
+                    let event = unmatched.remove(index);
+                    update_stmt.execute(params![
+                        event.event_id.id(),
+                        cluster.start_ts,
+                        cluster.end_ts,
+                        cluster.place,
+                    ])?;
+                } else {
+                    insert_stmt.execute(params![
+                        cluster.start_ts,
+                        cluster.end_ts,
+                        cluster.place
+                    ])?;
+                }
+            }
+
+            for event in unmatched {
+                delete_stmt.execute([event.event_id.id()])?;
+            }
+        }
+
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Sets the name of an event. None restores the automatic title.
+    pub fn rename(&mut self, event_id: EventId, name: Option<&str>) -> Result<()> {
+        let con = self.con.lock().unwrap();
+        let mut stmt = con.prepare("UPDATE events SET name = ?2 WHERE event_id = ?1")?;
+        stmt.execute(params![event_id.id(), name])?;
+        Ok(())
+    }
+
+    fn to_event(&self, row: &Row<'_>) -> rusqlite::Result<Event> {
+        Ok(Event {
+            event_id: row.get("event_id").map(EventId::new)?,
+            start_ts: row.get("start_ts")?,
+            end_ts: row.get("end_ts")?,
+            place: row.get("place")?,
+            name: row.get("name")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database;
+    use chrono::{DateTime, Utc};
+
+    fn ts(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    fn cluster(start_ts: &str, end_ts: &str, place: &str) -> Cluster {
+        Cluster {
+            start_ts: ts(start_ts),
+            end_ts: ts(end_ts),
+            place: Some(place.into()),
+        }
+    }
+
+    #[test]
+    fn test_update_keeps_names() {
+        let con = database::setup_in_memory().unwrap();
+        let mut repo = Repository::open(Arc::new(Mutex::new(con))).unwrap();
+
+        repo.update(&[
+            cluster("2023-03-01T10:00:00Z", "2023-03-05T10:00:00Z", "Lisbon"),
+            cluster("2023-04-01T10:00:00Z", "2023-04-01T18:00:00Z", "Party"),
+        ])
+        .unwrap();
+
+        let events = repo.all().unwrap();
+        assert_eq!(2, events.len());
+        repo.rename(events[0].event_id, Some("Portugal")).unwrap();
+
+        // More pictures added to end of first event, and second event is gone.
+        repo.update(&[cluster(
+            "2023-03-01T10:00:00Z",
+            "2023-03-06T10:00:00Z",
+            "Lisbon",
+        )])
+        .unwrap();
+
+        let events = repo.all().unwrap();
+        assert_eq!(1, events.len());
+        assert_eq!(Some("Portugal".to_string()), events[0].name);
+        assert_eq!(ts("2023-03-06T10:00:00Z"), events[0].end_ts);
+    }
+}
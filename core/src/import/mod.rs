@@ -0,0 +1,15 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Importing pictures and videos into the library, optionally organizing them
+//! into folders with a template such as `{year}/{month}/{filename}`.
+
+pub mod organizer;
+pub mod template;
+
+pub use organizer::Organizer;
+pub use organizer::Placement;
+pub use organizer::Summary;
+pub use organizer::Transfer;
+pub use template::Template;
@@ -0,0 +1,432 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::template::Template;
+use crate::photo;
+use crate::video;
+
+use anyhow::*;
+use chrono::{DateTime, Local, NaiveDate};
+use strum::{AsRefStr, EnumString, FromRepr};
+use walkdir::WalkDir;
+
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::result::Result::Ok;
+
+use tracing::{error, info};
+
+/// How to bring files into the library.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, EnumString, AsRefStr, FromRepr)]
+#[repr(u32)]
+pub enum Transfer {
+    /// Leave the original files where they are.
+    #[default]
+    Copy,
+
+    /// Remove the original files after they are in the library.
+    Move,
+}
+
+/// Where an imported file will go.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placement {
+    /// File being imported.
+    pub source: PathBuf,
+
+    /// Path in library for file. If the file is a duplicate, then this is the
+    /// path of the file already in the library.
+    pub destination: PathBuf,
+
+    /// File is already in the library, so won't be imported.
+    pub is_duplicate: bool,
+}
+
+/// Counts of files processed by an import.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+    pub imported: usize,
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+/// Puts imported pictures and videos into the library, following a folder template.
+#[derive(Debug, Clone)]
+pub struct Organizer {
+    library_base_dir: PathBuf,
+    template: Template,
+}
+
+impl Organizer {
+    pub fn new(library_base_dir: &Path, template: Template) -> Self {
+        Self {
+            library_base_dir: PathBuf::from(library_base_dir),
+            template,
+        }
+    }
+
+    /// Find the pictures and videos to import from files and folders chosen by the user.
+    /// Folders are searched, and files that aren't pictures or videos are ignored.
+    pub fn find_files(paths: &[PathBuf]) -> Vec<PathBuf> {
+        paths
+            .iter()
+            .flat_map(WalkDir::new)
+            .filter_map(|entry| {
+                entry
+                    .inspect_err(|e| error!("Failed walking: {:?}", e))
+                    .ok()
+            })
+            .map(|entry| entry.into_path())
+            .filter(|path| path.is_file())
+            .filter(|path| photo::scanner::is_picture(path) || video::scanner::is_video(path))
+            .collect()
+    }
+
+    /// Work out where each file will go, without changing anything.
+    /// Files whose destination is already taken get a numbered suffix, unless the existing
+    /// file has identical contents, in which case the file is a duplicate and is skipped.
+    pub fn plan(&self, sources: &[PathBuf]) -> Vec<Placement> {
+        let mut taken: HashSet<PathBuf> = HashSet::new();
+
+        sources
+            .iter()
+            .map(|source| {
+                let date = capture_date(source);
+                let preferred = self
+                    .library_base_dir
+                    .join(self.template.render(date, source));
+
+                let mut destination = preferred.clone();
+                let mut count = 1;
+
+                loop {
+                    if taken.contains(&destination) {
+                        // Another file in this import is going here.
+                    } else if !destination.exists() {
+                        break;
+                    } else if is_same_content(source, &destination) {
+                        return Placement {
+                            source: source.clone(),
+                            destination,
+                            is_duplicate: true,
+                        };
+                    }
+                    destination = numbered(&preferred, count);
+                    count += 1;
+                }
+
+                taken.insert(destination.clone());
+
+                Placement {
+                    source: source.clone(),
+                    destination,
+                    is_duplicate: false,
+                }
+            })
+            .collect()
+    }
+
+    /// Copy or move files into the library as planned.
+    /// A file that fails is logged and counted, and the rest of the import carries on.
+    pub fn execute(&self, placements: &[Placement], transfer: Transfer) -> Summary {
+        let mut summary = Summary::default();
+
+        for placement in placements {
+            if placement.is_duplicate {
+                summary.skipped += 1;
+                continue;
+            }
+
+            match transfer_file(&placement.source, &placement.destination, transfer) {
+                Ok(_) => {
+                    summary.imported += 1;
+                }
+                Err(e) => {
+                    error!(
+                        "Failed importing {:?} to {:?}: {:?}",
+                        placement.source, placement.destination, e
+                    );
+                    summary.failed += 1;
+                }
+            }
+        }
+
+        info!(
+            "Imported {} files into {:?}. Skipped {}. Failed {}.",
+            summary.imported, self.library_base_dir, summary.skipped, summary.failed
+        );
+
+        summary
+    }
+}
+
+/// Day a picture or video was taken, in the local time of the camera if known.
+/// Falls back to the file's modification time if there is no metadata.
+fn capture_date(path: &Path) -> NaiveDate {
+    let created_at = if photo::scanner::is_picture(path) {
+        photo::metadata::from_path(path)
+            .ok()
+            .and_then(|m| m.created_at)
+            .map(|ts| ts.naive_local().date())
+    } else if video::scanner::is_video(path) {
+        video::metadata::from_path(path)
+            .ok()
+            .and_then(|m| m.created_at)
+            .map(|ts| ts.with_timezone(&Local).date_naive())
+    } else {
+        None
+    };
+
+    created_at.unwrap_or_else(|| {
+        fs::metadata(path)
+            .and_then(|m| m.modified())
+            .map(|ts| DateTime::<Local>::from(ts).date_naive())
+            .unwrap_or_else(|_| Local::now().date_naive())
+    })
+}
+
+/// Path with a number added to the file name, such as `IMG_0001 (2).jpg`.
+fn numbered(path: &Path, count: usize) -> PathBuf {
+    let mut file_name = OsString::from(path.file_stem().unwrap_or_default());
+    file_name.push(format!(" ({})", count));
+    if let Some(extension) = path.extension() {
+        file_name.push(".");
+        file_name.push(extension);
+    }
+    path.with_file_name(file_name)
+}
+
+/// Do two files have identical contents?
+fn is_same_content(a: &Path, b: &Path) -> bool {
+    let same = || -> Result<bool> {
+        if fs::metadata(a)?.len() != fs::metadata(b)?.len() {
+            return Ok(false);
+        }
+
+        let mut a = BufReader::new(fs::File::open(a)?);
+        let mut b = BufReader::new(fs::File::open(b)?);
+        let mut a_buf = vec![0u8; 64 * 1024];
+        let mut b_buf = vec![0u8; 64 * 1024];
+
+        loop {
+            let a_len = read_full(&mut a, &mut a_buf)?;
+            let b_len = read_full(&mut b, &mut b_buf)?;
+            if a_buf[..a_len] != b_buf[..b_len] {
+                return Ok(false);
+            }
+            if a_len == 0 {
+                return Ok(true);
+            }
+        }
+    };
+
+    same().unwrap_or(false)
+}
+
+/// Fill buffer unless the end of file is reached first. Returns bytes read.
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+    while len < buf.len() {
+        match reader.read(&mut buf[len..])? {
+            0 => break,
+            n => len += n,
+        }
+    }
+    Ok(len)
+}
+
+/// Copy or move a file, never replacing a file already at the destination.
+fn transfer_file(source: &Path, destination: &Path, transfer: Transfer) -> Result<()> {
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    if transfer == Transfer::Move {
+        // Link then delete instead of renaming, because renaming would replace a file
+        // created at the destination since the import was planned. Linking fails if
+        // the source is on a different file system, such as a camera's memory card,
+        // in which case fall back to copying and deleting.
+        match fs::hard_link(source, destination) {
+            Ok(()) => {
+                fs::remove_file(source)?;
+                return Ok(());
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => return Err(e.into()),
+            Err(_) => {}
+        }
+    }
+
+    copy_new(source, destination)?;
+
+    if transfer == Transfer::Move {
+        fs::remove_file(source)?;
+    }
+
+    Ok(())
+}
+
+/// Copy a file to a destination that must not already exist.
+/// Keeps the modification time, which is used for dating files without metadata.
+fn copy_new(source: &Path, destination: &Path) -> Result<()> {
+    let mut reader = fs::File::open(source)?;
+    let mut writer = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(destination)?;
+
+    let result = io::copy(&mut reader, &mut writer)
+        .map_err(anyhow::Error::from)
+        .and_then(|_| {
+            let modified = reader.metadata()?.modified()?;
+            writer.set_modified(modified)?;
+            Ok(())
+        });
+
+    if result.is_err() {
+        // Don't leave a partial copy in the library.
+        let _ = fs::remove_file(destination);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    /// Write a file that is dated 2021-06-15 by its modification time.
+    fn write(path: &Path, contents: &[u8]) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_623_758_400);
+        fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    }
+
+    fn organizer(library: &Path) -> Organizer {
+        let template = Template::parse("{year}/{month}/{filename}").unwrap();
+        Organizer::new(library, template)
+    }
+
+    #[test]
+    fn find_files_skips_unsupported() {
+        let dir = tempfile::tempdir().unwrap();
+        write(&dir.path().join("a.jpg"), b"a");
+        write(&dir.path().join("DCIM/b.MP4"), b"b");
+        write(&dir.path().join("DCIM/notes.txt"), b"c");
+
+        let mut files = Organizer::find_files(&[dir.path().to_path_buf()]);
+        files.sort();
+
+        assert_eq!(
+            vec![dir.path().join("DCIM/b.MP4"), dir.path().join("a.jpg")],
+            files
+        );
+    }
+
+    #[test]
+    fn plan_numbers_collisions_and_skips_duplicates() {
+        let library = tempfile::tempdir().unwrap();
+        let camera = tempfile::tempdir().unwrap();
+
+        write(&library.path().join("2021/06/same.jpg"), b"same");
+        write(&library.path().join("2021/06/clash.jpg"), b"old");
+
+        let sources = vec![
+            camera.path().join("same.jpg"),
+            camera.path().join("clash.jpg"),
+            camera.path().join("other/clash.jpg"),
+        ];
+        write(&sources[0], b"same");
+        write(&sources[1], b"new");
+        write(&sources[2], b"newer");
+
+        let placements = organizer(library.path()).plan(&sources);
+
+        let destinations: Vec<(PathBuf, bool)> = placements
+            .iter()
+            .map(|p| (p.destination.clone(), p.is_duplicate))
+            .collect();
+
+        assert_eq!(
+            vec![
+                (library.path().join("2021/06/same.jpg"), true),
+                (library.path().join("2021/06/clash (1).jpg"), false),
+                (library.path().join("2021/06/clash (2).jpg"), false),
+            ],
+            destinations
+        );
+    }
+
+    #[test]
+    fn execute_copies_and_moves() {
+        let library = tempfile::tempdir().unwrap();
+        let camera = tempfile::tempdir().unwrap();
+
+        let copied = camera.path().join("copied.jpg");
+        let moved = camera.path().join("moved.jpg");
+        write(&copied, b"copied");
+        write(&moved, b"moved");
+
+        let organizer = organizer(library.path());
+
+        let summary = organizer.execute(
+            &organizer.plan(std::slice::from_ref(&copied)),
+            Transfer::Copy,
+        );
+        assert_eq!(1, summary.imported);
+        assert!(copied.exists());
+        assert_eq!(
+            b"copied".to_vec(),
+            fs::read(library.path().join("2021/06/copied.jpg")).unwrap()
+        );
+
+        let summary = organizer.execute(
+            &organizer.plan(std::slice::from_ref(&moved)),
+            Transfer::Move,
+        );
+        assert_eq!(1, summary.imported);
+        assert!(!moved.exists());
+        assert!(library.path().join("2021/06/moved.jpg").exists());
+
+        // Importing again is a no-op.
+        let summary = organizer.execute(
+            &organizer.plan(std::slice::from_ref(&copied)),
+            Transfer::Copy,
+        );
+        assert_eq!(
+            Summary {
+                imported: 0,
+                skipped: 1,
+                failed: 0
+            },
+            summary
+        );
+    }
+
+    #[test]
+    fn move_never_replaces_existing_file() {
+        let library = tempfile::tempdir().unwrap();
+        let camera = tempfile::tempdir().unwrap();
+
+        let source = camera.path().join("a.jpg");
+        let destination = library.path().join("a.jpg");
+        write(&source, b"new");
+
+        // Another program writes to the destination after the import was planned.
+        write(&destination, b"old");
+
+        assert!(transfer_file(&source, &destination, Transfer::Move).is_err());
+        assert_eq!(b"new".to_vec(), fs::read(&source).unwrap());
+        assert_eq!(b"old".to_vec(), fs::read(&destination).unwrap());
+    }
+}
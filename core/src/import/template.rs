@@ -0,0 +1,200 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use anyhow::*;
+use chrono::{Datelike, NaiveDate};
+
+use std::ffi::OsString;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+/// Template used when organizing is turned off. Files go in the library folder as they are.
+pub const FLAT: &str = "{filename}";
+
+/// Default template for organizing files into folders by year and month.
+pub const DEFAULT: &str = "{year}/{month}/{filename}";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    Year,
+    Month,
+    Day,
+    FileName,
+    Stem,
+    Extension,
+}
+
+/// Where to put an imported file, relative to the library folder.
+/// For example, `{year}/{month}/{filename}` puts a picture taken in March 2024
+/// at `2024/03/IMG_0001.jpg`.
+///
+/// Placeholders are:
+/// * `{year}` - four digit year the picture was taken.
+/// * `{month}` - two digit month the picture was taken.
+/// * `{day}` - two digit day of month the picture was taken.
+/// * `{filename}` - file name of the picture, such as `IMG_0001.jpg`.
+/// * `{stem}` - file name without the extension, such as `IMG_0001`.
+/// * `{extension}` - file name extension, such as `jpg`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    text: String,
+
+    /// Parts of each folder, followed by the parts of the file name.
+    components: Vec<Vec<Part>>,
+}
+
+impl Template {
+    pub fn parse(text: &str) -> Result<Template> {
+        let text = text.trim();
+        if text.is_empty() {
+            bail!("Template is empty");
+        }
+
+        let components = text
+            .split('/')
+            .map(|component| {
+                if component.is_empty() {
+                    bail!("Template must be a relative path without empty folder names");
+                }
+                if component == "." || component == ".." {
+                    bail!("Template must not contain '.' or '..' folders");
+                }
+                parse_component(component)
+            })
+            .collect::<Result<Vec<Vec<Part>>>>()?;
+
+        // Without the original file name, or its extension, imported files would
+        // overwrite each other or lose their type.
+        let file_name = components.last().expect("Template must have a file name");
+        let has_file_name = file_name.contains(&Part::FileName)
+            || (file_name.contains(&Part::Stem) && file_name.contains(&Part::Extension));
+        if !has_file_name {
+            bail!("Template must end with {{filename}} or {{stem}} and {{extension}}");
+        }
+
+        Ok(Template {
+            text: text.to_string(),
+            components,
+        })
+    }
+
+    /// Path for a file, relative to the library folder.
+    /// `date` is the day the picture or video was taken.
+    pub fn render(&self, date: NaiveDate, source: &Path) -> PathBuf {
+        let file_name = source.file_name().unwrap_or_default();
+        let stem = source.file_stem().unwrap_or_default();
+        let extension = source.extension().unwrap_or_default();
+
+        self.components
+            .iter()
+            .map(|parts| {
+                let mut component = OsString::new();
+                for part in parts {
+                    match part {
+                        Part::Text(text) => component.push(text),
+                        Part::Year => component.push(format!("{:04}", date.year())),
+                        Part::Month => component.push(format!("{:02}", date.month())),
+                        Part::Day => component.push(format!("{:02}", date.day())),
+                        Part::FileName => component.push(file_name),
+                        Part::Stem => component.push(stem),
+                        Part::Extension => component.push(extension),
+                    }
+                }
+                component
+            })
+            .collect()
+    }
+}
+
+impl Display for Template {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+/// Parse a folder or file name of a template into text and placeholders.
+fn parse_component(component: &str) -> Result<Vec<Part>> {
+    let mut parts = vec![];
+    let mut rest = component;
+
+    while let Some(start) = rest.find('{') {
+        if start > 0 {
+            parts.push(Part::Text(rest[..start].to_string()));
+        }
+
+        let Some(end) = rest[start..].find('}') else {
+            bail!("Placeholder in '{}' is missing a closing '}}'", component);
+        };
+
+        let part = match &rest[start + 1..start + end] {
+            "year" => Part::Year,
+            "month" => Part::Month,
+            "day" => Part::Day,
+            "filename" => Part::FileName,
+            "stem" => Part::Stem,
+            "extension" => Part::Extension,
+            other => bail!("Unknown placeholder {{{}}}", other),
+        };
+        parts.push(part);
+
+        rest = &rest[start + end + 1..];
+    }
+
+    if rest.contains('}') {
+        bail!("Placeholder in '{}' is missing an opening '{{'", component);
+    }
+
+    if !rest.is_empty() {
+        parts.push(Part::Text(rest.to_string()));
+    }
+
+    Ok(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_by_date() {
+        let template = Template::parse("Camera/{year}/{month}-{day}/{stem}.{extension}").unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 7).unwrap();
+        let path = template.render(date, Path::new("/media/phone/DCIM/IMG_0001.JPG"));
+        assert_eq!(PathBuf::from("Camera/2024/03-07/IMG_0001.JPG"), path);
+    }
+
+    #[test]
+    fn default_and_flat_templates_are_valid() {
+        let date = NaiveDate::from_ymd_opt(1999, 12, 31).unwrap();
+        let source = Path::new("party.mp4");
+
+        let path = Template::parse(DEFAULT).unwrap().render(date, source);
+        assert_eq!(PathBuf::from("1999/12/party.mp4"), path);
+
+        let path = Template::parse(FLAT).unwrap().render(date, source);
+        assert_eq!(PathBuf::from("party.mp4"), path);
+    }
+
+    #[test]
+    fn invalid_templates() {
+        assert!(Template::parse("").is_err());
+        assert!(Template::parse("/{year}/{filename}").is_err());
+        assert!(Template::parse("{year}//{filename}").is_err());
+        assert!(Template::parse("../{filename}").is_err());
+        assert!(Template::parse("{year}/{month}").is_err());
+        assert!(Template::parse("{year/{filename}").is_err());
+        assert!(Template::parse("year}/{filename}").is_err());
+        assert!(Template::parse("{camera}/{filename}").is_err());
+    }
+
+    #[test]
+    fn file_name_must_keep_stem_and_extension() {
+        assert!(Template::parse("{year}/{stem}").is_err());
+        assert!(Template::parse("{year}/{extension}").is_err());
+        assert!(Template::parse("{stem}/photo.{extension}").is_err());
+        assert!(Template::parse("{year}/{stem}.{extension}").is_ok());
+        assert!(Template::parse("{year}/{day}-{filename}").is_ok());
+    }
+}
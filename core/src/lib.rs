@@ -2,11 +2,17 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+pub mod caption;
 pub mod database;
+pub mod event;
+pub mod import;
 pub mod machine_learning;
+pub mod memory_movie;
+pub mod network_share;
 pub mod path_encoding;
 pub mod people;
 pub mod photo;
+pub mod quarantine;
 pub mod time;
 pub mod video;
 pub mod visual;
@@ -0,0 +1,138 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+/// How far to zoom into a picture. 1.2 shows 83% of the width and height.
+const ZOOM: f64 = 1.2;
+
+/// A rectangle in picture pixel coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub left: f64,
+    pub top: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rect {
+    /// Largest rectangle with the given aspect ratio (width / height) that fits
+    /// centered within a picture.
+    pub fn cover(picture_width: u32, picture_height: u32, aspect: f64) -> Rect {
+        let (picture_width, picture_height) = (picture_width as f64, picture_height as f64);
+        let (width, height) = if picture_width / picture_height > aspect {
+            (picture_height * aspect, picture_height)
+        } else {
+            (picture_width, picture_width / aspect)
+        };
+
+        Rect {
+            left: (picture_width - width) / 2.0,
+            top: (picture_height - height) / 2.0,
+            width,
+            height,
+        }
+    }
+
+    /// Rectangle scaled down by `ZOOM` and placed within this rectangle.
+    /// `x` and `y` are from 0.0 (left or top edge) to 1.0 (right or bottom edge).
+    fn zoomed(&self, x: f64, y: f64) -> Rect {
+        let width = self.width / ZOOM;
+        let height = self.height / ZOOM;
+        Rect {
+            left: self.left + (self.width - width) * x,
+            top: self.top + (self.height - height) * y,
+            width,
+            height,
+        }
+    }
+
+    fn lerp(&self, other: &Rect, t: f64) -> Rect {
+        Rect {
+            left: self.left + (other.left - self.left) * t,
+            top: self.top + (other.top - self.top) * t,
+            width: self.width + (other.width - self.width) * t,
+            height: self.height + (other.height - self.height) * t,
+        }
+    }
+}
+
+/// Slow pan and zoom across a picture, as popularised by Ken Burns documentaries.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KenBurns {
+    start: Rect,
+    end: Rect,
+}
+
+impl KenBurns {
+    /// Choose a pan or zoom for the nth picture of a movie with frames of the given aspect ratio.
+    /// Consecutive pictures get different movements so the movie doesn't feel repetitive.
+    pub fn new(index: usize, picture_width: u32, picture_height: u32, aspect: f64) -> KenBurns {
+        let cover = Rect::cover(picture_width, picture_height, aspect);
+
+        // Pan along whichever axis has more room to move.
+        let is_wide = picture_width as f64 / picture_height as f64 >= aspect;
+
+        let (start, end) = match index % 4 {
+            0 => (cover, cover.zoomed(0.5, 0.5)),
+            1 if is_wide => (cover.zoomed(0.0, 0.5), cover.zoomed(1.0, 0.5)),
+            1 => (cover.zoomed(0.5, 0.0), cover.zoomed(0.5, 1.0)),
+            2 => (cover.zoomed(0.5, 0.5), cover),
+            _ if is_wide => (cover.zoomed(1.0, 0.5), cover.zoomed(0.0, 0.5)),
+            _ => (cover.zoomed(0.5, 1.0), cover.zoomed(0.5, 0.0)),
+        };
+
+        KenBurns { start, end }
+    }
+
+    /// Visible part of picture at time `t`, from 0.0 (start of shot) to 1.0 (end of shot).
+    /// Movement eases in and out so shots don't start or stop abruptly.
+    pub fn at(&self, t: f64) -> Rect {
+        let t = t.clamp(0.0, 1.0);
+        let eased = t * t * (3.0 - 2.0 * t);
+        self.start.lerp(&self.end, eased)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cover() {
+        // 4:3 picture in 16:9 frame is cropped top and bottom
+        let rect = Rect::cover(4000, 3000, 16.0 / 9.0);
+        assert_eq!(0.0, rect.left);
+        assert_eq!(4000.0, rect.width);
+        assert_eq!(2250.0, rect.height);
+        assert_eq!(375.0, rect.top);
+
+        // Portrait picture in 16:9 frame is cropped top and bottom
+        let rect = Rect::cover(900, 1600, 16.0 / 9.0);
+        assert_eq!(900.0, rect.width);
+        assert!((rect.height - 506.25).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_ken_burns_stays_within_picture() {
+        for index in 0..4 {
+            for (width, height) in [(4000, 3000), (3000, 4000), (1920, 1080)] {
+                let kb = KenBurns::new(index, width, height, 16.0 / 9.0);
+                for step in 0..=10 {
+                    let rect = kb.at(step as f64 / 10.0);
+                    assert!(rect.left >= 0.0 && rect.top >= 0.0);
+                    assert!(rect.left + rect.width <= width as f64 + 0.001);
+                    assert!(rect.top + rect.height <= height as f64 + 0.001);
+                    assert!((rect.width / rect.height - 16.0 / 9.0).abs() < 0.001);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_ken_burns_endpoints() {
+        let kb = KenBurns::new(0, 1600, 900, 16.0 / 9.0);
+        assert_eq!(Rect::cover(1600, 900, 16.0 / 9.0), kb.at(0.0));
+        assert_eq!(kb.at(1.0), kb.at(2.0));
+        assert!(kb.at(1.0).width < kb.at(0.0).width);
+    }
+}
@@ -0,0 +1,296 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::ken_burns::KenBurns;
+
+use anyhow::*;
+
+use fast_image_resize as fr;
+use fr::images::Image;
+use fr::{ResizeOptions, Resizer};
+use futures::executor::block_on;
+
+use gdk4::prelude::TextureExt;
+use gdk4::{MemoryFormat, TextureDownloader};
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use gstreamer_app as gst_app;
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tracing::{debug, error, info};
+
+const WIDTH: u32 = 1280;
+const HEIGHT: u32 = 720;
+const FRAMES_PER_SECOND: u64 = 30;
+
+/// How long each picture is shown.
+const SHOT_SECONDS: u64 = 4;
+
+/// How long to fade in from black and out to black at the start and end of each shot.
+const FADE_SECONDS: f64 = 0.5;
+
+/// Video formats, in order of preference. H.264 plays almost everywhere, but
+/// the encoder isn't always installed, whereas the VP8 encoder usually is.
+const FORMATS: [(&str, &str, &str); 3] = [
+    ("x264enc", "mp4mux", "mp4"),
+    ("openh264enc", "mp4mux", "mp4"),
+    ("vp8enc", "webmmux", "webm"),
+];
+
+/// Renders memory movies with GStreamer.
+#[derive(Debug, Clone)]
+pub struct MovieMaker {
+    /// Base path for storing rendered movies
+    base_path: PathBuf,
+}
+
+impl MovieMaker {
+    pub fn build(base_path: &Path) -> Result<MovieMaker> {
+        let base_path = PathBuf::from(base_path).join("memory_movies");
+        std::fs::create_dir_all(&base_path)?;
+
+        gst::init()?;
+
+        Ok(MovieMaker { base_path })
+    }
+
+    /// Render a slideshow of pictures with a Ken Burns effect and return the path to the movie.
+    /// `progress` is called with the number of pictures rendered so far.
+    /// Rendering is abandoned if `stop` is set.
+    pub fn make<F>(
+        &self,
+        name: &str,
+        picture_paths: &[PathBuf],
+        stop: &AtomicBool,
+        progress: F,
+    ) -> Result<PathBuf>
+    where
+        F: Fn(usize),
+    {
+        let Some((encoder_name, muxer_name, extension)) = FORMATS
+            .into_iter()
+            .find(|(encoder, _, _)| gst::ElementFactory::find(encoder).is_some())
+        else {
+            bail!("No supported video encoder is installed");
+        };
+
+        let movie_path = self.base_path.join(format!("{}.{}", name, extension));
+        let temporary_movie_path = movie_path.with_extension(format!("tmp.{}", extension));
+
+        info!(
+            "Rendering {} pictures to {:?} with {}",
+            picture_paths.len(),
+            movie_path,
+            encoder_name
+        );
+
+        let caps = gst::Caps::builder("video/x-raw")
+            .field("format", "RGBA")
+            .field("width", WIDTH as i32)
+            .field("height", HEIGHT as i32)
+            .field("framerate", gst::Fraction::new(FRAMES_PER_SECOND as i32, 1))
+            .build();
+
+        // Block when the pipeline is full, otherwise every frame of the movie would be
+        // queued in memory before the encoder can catch up.
+        let source = gst_app::AppSrc::builder()
+            .caps(&caps)
+            .format(gst::Format::Time)
+            .block(true)
+            .build();
+
+        let convert = gst::ElementFactory::make("videoconvert").build()?;
+        let encoder = gst::ElementFactory::make(encoder_name).build()?;
+        let muxer = gst::ElementFactory::make(muxer_name).build()?;
+        let sink = gst::ElementFactory::make("filesink")
+            .property(
+                "location",
+                temporary_movie_path.to_string_lossy().to_string(),
+            )
+            .build()?;
+
+        let pipeline = gst::Pipeline::default();
+        let elements = [
+            source.upcast_ref::<gst::Element>(),
+            &convert,
+            &encoder,
+            &muxer,
+            &sink,
+        ];
+        pipeline.add_many(elements)?;
+        gst::Element::link_many(elements)?;
+
+        pipeline.set_state(gst::State::Playing)?;
+
+        let result = self.render(&source, picture_paths, stop, progress);
+
+        let result = result.and_then(|_| {
+            source.end_of_stream()?;
+            wait_for_eos(&pipeline)
+        });
+
+        pipeline.set_state(gst::State::Null)?;
+
+        if let Err(e) = result {
+            let _ = std::fs::remove_file(&temporary_movie_path);
+            return Err(e);
+        }
+
+        std::fs::rename(&temporary_movie_path, &movie_path)?;
+
+        Ok(movie_path)
+    }
+
+    fn render<F>(
+        &self,
+        source: &gst_app::AppSrc,
+        picture_paths: &[PathBuf],
+        stop: &AtomicBool,
+        progress: F,
+    ) -> Result<()>
+    where
+        F: Fn(usize),
+    {
+        let frames_per_shot = FRAMES_PER_SECOND * SHOT_SECONDS;
+        let frame_duration = gst::ClockTime::SECOND / FRAMES_PER_SECOND;
+        let aspect = WIDTH as f64 / HEIGHT as f64;
+
+        let mut resizer = Resizer::new();
+        let mut frame_number = 0;
+        let mut shot_count = 0;
+
+        for (index, picture_path) in picture_paths.iter().enumerate() {
+            if stop.load(Ordering::Relaxed) {
+                bail!("Rendering stopped");
+            }
+
+            debug!("Rendering picture {:?}", picture_path);
+
+            // One bad picture shouldn't spoil the whole movie, so leave it out.
+            let picture = match load(picture_path, &mut resizer) {
+                Ok(picture) => picture,
+                Err(e) => {
+                    error!("Leaving {:?} out of memory movie: {:?}", picture_path, e);
+                    progress(index + 1);
+                    continue;
+                }
+            };
+
+            let ken_burns = KenBurns::new(shot_count, picture.width(), picture.height(), aspect);
+
+            for shot_frame in 0..frames_per_shot {
+                let t = shot_frame as f64 / frames_per_shot as f64;
+                let rect = ken_burns.at(t);
+
+                let mut frame = Image::new(WIDTH, HEIGHT, fr::PixelType::U8x4);
+                resizer.resize(
+                    &picture,
+                    &mut frame,
+                    &ResizeOptions::new().crop(rect.left, rect.top, rect.width, rect.height),
+                )?;
+
+                let mut pixels = frame.into_vec();
+                fade(&mut pixels, t * SHOT_SECONDS as f64);
+
+                let mut buffer = gst::Buffer::from_mut_slice(pixels);
+                {
+                    let buffer = buffer.get_mut().expect("Buffer must be writable");
+                    buffer.set_pts(frame_duration * frame_number);
+                    buffer.set_duration(frame_duration);
+                }
+                source.push_buffer(buffer)?;
+                frame_number += 1;
+            }
+
+            shot_count += 1;
+            progress(index + 1);
+        }
+
+        if shot_count == 0 {
+            bail!("None of the pictures could be loaded");
+        }
+
+        Ok(())
+    }
+}
+
+/// Load a picture in a Glycin sandbox, so orientation is corrected the same way
+/// as for the viewer, and shrink it so that it is no larger than needed for zooming.
+fn load(picture_path: &Path, resizer: &mut Resizer) -> Result<Image<'static>> {
+    let texture = block_on(async {
+        let file = gio::File::for_path(picture_path);
+        let loader = glycin::Loader::new(file);
+        let image = loader.load().await?;
+        let frame = image.next_frame().await?;
+        Ok(frame.texture())
+    })?;
+
+    let width = texture.width() as u32;
+    let height = texture.height() as u32;
+
+    let mut downloader = TextureDownloader::new(&texture);
+    downloader.set_format(MemoryFormat::R8g8b8a8);
+    let (bytes, stride) = downloader.download_bytes();
+
+    // Rows of the texture might be padded, but rows of the image must not be.
+    let row_length = width as usize * 4;
+    let pixels: Vec<u8> = bytes
+        .chunks(stride)
+        .take(height as usize)
+        .flat_map(|row| &row[..row_length])
+        .copied()
+        .collect();
+
+    let image = Image::from_vec_u8(width, height, pixels, fr::PixelType::U8x4)?;
+
+    // Fully zoomed in, a shot shows 1/1.2 of the picture, so twice the frame size is plenty.
+    let scale = f64::max(
+        2.0 * WIDTH as f64 / width as f64,
+        2.0 * HEIGHT as f64 / height as f64,
+    );
+
+    if scale >= 1.0 {
+        return Ok(image);
+    }
+
+    let mut picture = Image::new(
+        (width as f64 * scale) as u32,
+        (height as f64 * scale) as u32,
+        fr::PixelType::U8x4,
+    );
+    resizer.resize(&image, &mut picture, None)?;
+    Ok(picture)
+}
+
+/// Fade RGBA pixels in from black and out to black at the start and end of a shot.
+/// `seconds` is the time since the start of the shot.
+fn fade(pixels: &mut [u8], seconds: f64) {
+    let remaining = SHOT_SECONDS as f64 - seconds;
+    let brightness = (seconds.min(remaining) / FADE_SECONDS).min(1.0);
+    if brightness >= 1.0 {
+        return;
+    }
+
+    for pixel in pixels.chunks_exact_mut(4) {
+        for channel in &mut pixel[0..3] {
+            *channel = (*channel as f64 * brightness) as u8;
+        }
+    }
+}
+
+fn wait_for_eos(pipeline: &gst::Pipeline) -> Result<()> {
+    let bus = pipeline.bus().context("Pipeline has no bus")?;
+    for msg in bus.iter_timed(gst::ClockTime::NONE) {
+        match msg.view() {
+            gst::MessageView::Eos(..) => return Ok(()),
+            gst::MessageView::Error(err) => {
+                bail!("GStreamer error: {} ({:?})", err.error(), err.debug());
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,13 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Memory movies are short slideshow videos assembled from a set of pictures,
+//! such as an album or the pictures taken on this day in previous years.
+
+pub mod ken_burns;
+pub mod maker;
+pub mod selection;
+
+pub use ken_burns::KenBurns;
+pub use maker::MovieMaker;
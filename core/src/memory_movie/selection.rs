@@ -0,0 +1,119 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::Visual;
+use chrono::{Datelike, NaiveDate, TimeDelta};
+use std::sync::Arc;
+
+/// Pictures taken closer together than this are treated as a burst of the same scene.
+const BURST_GAP_SECONDS: i64 = 10;
+
+/// Pick the pictures to show in a memory movie.
+///
+/// Fotema doesn't score picture quality, so "best" means a spread of distinct moments:
+/// videos are skipped, only one picture is kept from each burst of shots, and if there are
+/// still more than `max` pictures then pictures are sampled evenly over time.
+/// Returned pictures are in chronological order.
+pub fn best_shots(visuals: &[Arc<Visual>], max: usize) -> Vec<Arc<Visual>> {
+    let mut candidates: Vec<&Arc<Visual>> = visuals
+        .iter()
+        .filter(|v| v.picture_path.is_some())
+        .collect();
+
+    candidates.sort_by_key(|v| v.ordering_ts);
+
+    let burst_gap = TimeDelta::seconds(BURST_GAP_SECONDS);
+    let mut moments: Vec<&Arc<Visual>> = Vec::new();
+    for visual in candidates {
+        let is_burst = moments
+            .last()
+            .is_some_and(|last| visual.ordering_ts - last.ordering_ts < burst_gap);
+        if !is_burst {
+            moments.push(visual);
+        }
+    }
+
+    if moments.len() > max {
+        moments = (0..max).map(|i| moments[i * moments.len() / max]).collect();
+    }
+
+    moments.into_iter().cloned().collect()
+}
+
+/// Pictures taken on the same day and month as `today` in previous years.
+pub fn on_this_day(visuals: &[Arc<Visual>], today: NaiveDate) -> Vec<Arc<Visual>> {
+    visuals
+        .iter()
+        .filter(|v| {
+            let date = v.ordering_ts.date_naive();
+            date.month() == today.month() && date.day() == today.day() && date.year() < today.year()
+        })
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PictureId, VisualId};
+    use chrono::{DateTime, Utc};
+    use std::path::PathBuf;
+
+    fn picture(id: i64, ordering_ts: &str) -> Arc<Visual> {
+        Arc::new(Visual {
+            visual_id: VisualId::new(format!("{}", id)),
+            parent_path: PathBuf::from("/pics"),
+            thumbnail_path: None,
+            video_id: None,
+            video_path: None,
+            video_transcoded_path: None,
+            video_duration: None,
+            video_orientation: None,
+            picture_id: Some(PictureId::new(id)),
+            picture_path: Some(PathBuf::from(format!("/pics/{}.jpg", id))),
+            picture_orientation: None,
+            motion_photo_video_path: None,
+            ordering_ts: ordering_ts.parse::<DateTime<Utc>>().unwrap(),
+            is_selfie: None,
+            is_live_photo: false,
+            is_transcode_required: None,
+            location: None,
+        })
+    }
+
+    fn ids(visuals: &[Arc<Visual>]) -> Vec<String> {
+        visuals.iter().map(|v| v.visual_id.to_string()).collect()
+    }
+
+    #[test]
+    fn test_best_shots_skips_bursts() {
+        let visuals = vec![
+            picture(1, "2024-06-01T10:00:00Z"),
+            picture(2, "2024-06-01T10:00:03Z"),
+            picture(3, "2024-06-01T10:00:05Z"),
+            picture(4, "2024-06-01T11:00:00Z"),
+        ];
+        assert_eq!(vec!["1", "4"], ids(&best_shots(&visuals, 10)));
+    }
+
+    #[test]
+    fn test_best_shots_samples_evenly() {
+        let visuals: Vec<Arc<Visual>> = (0..10)
+            .map(|i| picture(i, &format!("2024-06-{:02}T10:00:00Z", i + 1)))
+            .collect();
+        assert_eq!(vec!["0", "2", "4", "6", "8"], ids(&best_shots(&visuals, 5)));
+    }
+
+    #[test]
+    fn test_on_this_day() {
+        let visuals = vec![
+            picture(1, "2020-06-01T10:00:00Z"),
+            picture(2, "2021-06-02T10:00:00Z"),
+            picture(3, "2022-06-01T10:00:00Z"),
+            picture(4, "2024-06-01T10:00:00Z"),
+        ];
+        let today = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        assert_eq!(vec!["1", "3"], ids(&on_this_day(&visuals, today)));
+    }
+}
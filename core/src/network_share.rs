@@ -0,0 +1,248 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Support for pictures libraries on network shares, such as NFS or SMB shares
+//! mounted by the kernel or by gvfs.
+//!
+//! A network share can disappear at any time. When it does, the library directory
+//! either can't be read or, if the share was mounted directly on the library directory,
+//! is an empty mount point. Either way every picture looks deleted, so background tasks
+//! must not scan or clean the library while it is offline. Thumbnails and metadata are
+//! kept in Fotema's cache and database, so the library can still be browsed.
+
+use std::path::{Path, PathBuf};
+
+/// File system types for network shares, as named in /proc/mounts and /etc/fstab.
+const NETWORK_FS_TYPES: [&str; 13] = [
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb3",
+    "smbfs",
+    "afs",
+    "ceph",
+    "glusterfs",
+    "davfs",
+    "fuse.sshfs",
+    "fuse.rclone",
+    "fuse.glusterfs",
+    "fuse.gvfsd-fuse",
+];
+
+/// Can the pictures library be read?
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Availability {
+    /// Library directory can be read.
+    #[default]
+    Online,
+
+    /// Library directory can't be read, probably because it is on a
+    /// network share that isn't mounted.
+    Offline,
+}
+
+/// Check if the library directory can be read.
+/// `has_items` is whether the library database already has pictures or videos,
+/// in which case an empty library directory on a network share, or on a mount point
+/// with nothing mounted, is an unmounted share.
+///
+/// Note that this blocks until the share responds, so call it from a background thread.
+pub fn availability(library_base_dir: &Path, has_items: bool) -> Availability {
+    check_availability(library_base_dir, has_items, may_be_unmounted)
+}
+
+fn check_availability(
+    library_base_dir: &Path,
+    has_items: bool,
+    may_be_unmounted: impl Fn(&Path) -> bool,
+) -> Availability {
+    let is_empty = match std::fs::read_dir(library_base_dir) {
+        Ok(mut entries) => entries.next().is_none(),
+        Err(_) => return Availability::Offline,
+    };
+
+    // An empty local directory really is empty, such as when the user has moved their
+    // pictures somewhere else, and the library must be cleaned to match.
+    if is_empty && has_items && may_be_unmounted(library_base_dir) {
+        Availability::Offline
+    } else {
+        Availability::Online
+    }
+}
+
+/// Could a file system that should be mounted on or above the path be missing?
+fn may_be_unmounted(path: &Path) -> bool {
+    if is_network_path(path) {
+        return true;
+    }
+
+    let fstab = std::fs::read_to_string("/etc/fstab").unwrap_or_default();
+    let mounts = std::fs::read_to_string("/proc/self/mounts").unwrap_or_default();
+    is_unmounted_mount_point(&fstab, &mounts, path)
+}
+
+/// Is the library directory on a network share?
+/// Checks the mounted file systems, and also the configured file systems so that
+/// a share is still recognised when it isn't mounted.
+pub fn is_network_path(path: &Path) -> bool {
+    ["/proc/self/mounts", "/etc/fstab"].iter().any(|table| {
+        std::fs::read_to_string(table)
+            .ok()
+            .and_then(|table| network_fs_type(&table, path))
+            .is_some()
+    })
+}
+
+/// Find the network file system type for a path from a mount table in the format
+/// of /proc/mounts or /etc/fstab. Returns None if the path isn't on a network share.
+fn network_fs_type(table: &str, path: &Path) -> Option<String> {
+    table
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _device = fields.next()?;
+            let mount_point = unescape(fields.next()?);
+            let fs_type = fields.next()?;
+            path.starts_with(&mount_point)
+                .then(|| (mount_point, fs_type.to_string()))
+        })
+        // Most specific mount point wins, so a local disk mounted under a share isn't a share.
+        .max_by_key(|(mount_point, _)| mount_point.len())
+        .map(|(_, fs_type)| fs_type)
+        .filter(|fs_type| NETWORK_FS_TYPES.contains(&fs_type.as_str()))
+}
+
+/// Is the path a mount point in fstab that has nothing mounted on it, according to mounts?
+fn is_unmounted_mount_point(fstab: &str, mounts: &str, path: &Path) -> bool {
+    let is_mount_point = |table: &str| mount_points(table).any(|mount_point| mount_point == path);
+    is_mount_point(fstab) && !is_mount_point(mounts)
+}
+
+/// Mount points from a mount table in the format of /proc/mounts or /etc/fstab.
+fn mount_points(table: &str) -> impl Iterator<Item = PathBuf> + '_ {
+    table
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(|mount_point| PathBuf::from(unescape(mount_point)))
+}
+
+/// Mount tables escape spaces, tabs, and new lines in mount points as octal.
+fn unescape(mount_point: &str) -> String {
+    mount_point
+        .replace("\\040", " ")
+        .replace("\\011", "\t")
+        .replace("\\012", "\n")
+        .replace("\\134", "\\")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOUNTS: &str = "\
+sysfs /sys sysfs rw,nosuid,nodev,noexec,relatime 0 0
+/dev/nvme0n1p2 / ext4 rw,relatime 0 0
+nas:/volume1/photos /mnt/nas nfs4 rw,relatime,vers=4.2 0 0
+//nas/Family\\040Photos /mnt/family\\040photos cifs rw,relatime 0 0
+/dev/sdb1 /mnt/nas/usb ext4 rw,relatime 0 0
+gvfsd-fuse /run/user/1000/gvfs fuse.gvfsd-fuse rw,nosuid,nodev 0 0
+";
+
+    #[test]
+    fn finds_network_mounts() {
+        assert_eq!(
+            Some("nfs4".to_string()),
+            network_fs_type(MOUNTS, Path::new("/mnt/nas/2024"))
+        );
+        assert_eq!(
+            Some("cifs".to_string()),
+            network_fs_type(MOUNTS, Path::new("/mnt/family photos/2024"))
+        );
+        assert_eq!(
+            Some("fuse.gvfsd-fuse".to_string()),
+            network_fs_type(
+                MOUNTS,
+                Path::new("/run/user/1000/gvfs/smb-share:server=nas,share=photos")
+            )
+        );
+    }
+
+    #[test]
+    fn ignores_local_mounts() {
+        assert_eq!(
+            None,
+            network_fs_type(MOUNTS, Path::new("/home/me/Pictures"))
+        );
+        assert_eq!(
+            None,
+            network_fs_type(MOUNTS, Path::new("/mnt/nas/usb/2024"))
+        );
+        assert_eq!(None, network_fs_type(MOUNTS, Path::new("/mnt/nasty")));
+    }
+
+    #[test]
+    fn fstab_comments_are_ignored() {
+        let fstab = "# nas:/photos /home/me/Pictures nfs defaults 0 0\n\
+                     nas:/photos /srv/photos nfs noauto,x-systemd.automount 0 0";
+        assert_eq!(None, network_fs_type(fstab, Path::new("/home/me/Pictures")));
+        assert_eq!(
+            Some("nfs".to_string()),
+            network_fs_type(fstab, Path::new("/srv/photos"))
+        );
+    }
+
+    #[test]
+    fn empty_share_is_offline_if_library_has_items() {
+        let dir = tempfile::tempdir().unwrap();
+        let is_share = |_: &Path| true;
+        assert_eq!(
+            Availability::Online,
+            check_availability(dir.path(), false, is_share)
+        );
+        assert_eq!(
+            Availability::Offline,
+            check_availability(dir.path(), true, is_share)
+        );
+
+        std::fs::write(dir.path().join("picture.jpg"), b"").unwrap();
+        assert_eq!(
+            Availability::Online,
+            check_availability(dir.path(), true, is_share)
+        );
+
+        let missing = dir.path().join("missing");
+        assert_eq!(Availability::Offline, availability(&missing, false));
+    }
+
+    #[test]
+    fn empty_local_directory_is_online() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(Availability::Online, availability(dir.path(), true));
+    }
+
+    #[test]
+    fn finds_unmounted_mount_points() {
+        let fstab = "nas:/photos /mnt/nas nfs4 noauto 0 0\n\
+                     /dev/sdc1 /media/photos ext4 noauto,nofail 0 0";
+        assert!(is_unmounted_mount_point(
+            fstab,
+            "",
+            Path::new("/media/photos")
+        ));
+        assert!(!is_unmounted_mount_point(
+            fstab,
+            MOUNTS,
+            Path::new("/mnt/nas")
+        ));
+        assert!(!is_unmounted_mount_point(
+            fstab,
+            "",
+            Path::new("/home/me/Pictures")
+        ));
+    }
+}
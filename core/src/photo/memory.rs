@@ -0,0 +1,429 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::metadata;
+use super::model::{MotionPhotoVideo, Picture, PictureId, ScannedFile};
+use super::motion_photo;
+use super::store::PictureStore;
+use super::Metadata;
+use crate::quarantine;
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// A picture and its processing state.
+#[derive(Debug, Clone)]
+struct Entry {
+    picture_id: PictureId,
+    scanned: ScannedFile,
+    metadata: Option<Metadata>,
+    metadata_version: u32,
+    thumbnail_path: Option<PathBuf>,
+    motion_photo_version: u32,
+    motion_photo: Option<MotionPhotoVideo>,
+    is_broken: bool,
+    quarantine: Option<(quarantine::Stage, String)>,
+    is_face_scanned: bool,
+
+    /// When picture was first added. Used for ordering if the picture has no timestamps.
+    added_at: DateTime<Utc>,
+}
+
+impl Entry {
+    /// Same precedence as the ordering_ts column of the Sqlite repository.
+    /// The Sqlite repository falls back to the current time, which would make the order
+    /// of pictures without timestamps change between calls, so fall back to the time the
+    /// picture was added instead.
+    fn ordering_ts(&self) -> DateTime<Utc> {
+        self.metadata
+            .as_ref()
+            .and_then(|m| m.created_at.or(m.modified_at))
+            .map(|ts| ts.to_utc())
+            .or(self.scanned.fs_created_at)
+            .or(self.scanned.fs_modified_at)
+            .unwrap_or(self.added_at)
+    }
+
+    fn to_picture(&self) -> Picture {
+        Picture {
+            picture_id: self.picture_id,
+            path: self.scanned.path.clone(),
+            thumbnail_path: self.thumbnail_path.clone(),
+            ordering_ts: self.ordering_ts(),
+            is_selfie: self.metadata.as_ref().map(|m| m.is_selfie()),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    next_id: i64,
+    entries: Vec<Entry>,
+}
+
+/// Repository of picture metadata held in memory.
+/// Intended for tests and for running without a database.
+/// Clones share the same state, like clones of the Sqlite `Repository` share a connection.
+#[derive(Debug, Clone)]
+pub struct MemoryRepository {
+    /// Base path to picture library on file system
+    library_base_path: PathBuf,
+
+    state: Arc<Mutex<State>>,
+}
+
+impl MemoryRepository {
+    pub fn new(library_base_path: &Path) -> MemoryRepository {
+        MemoryRepository {
+            library_base_path: PathBuf::from(library_base_path),
+            state: Arc::new(Mutex::new(State::default())),
+        }
+    }
+
+    /// Gets error for a quarantined picture.
+    pub fn quarantined(&self, picture_id: PictureId) -> Option<(quarantine::Stage, String)> {
+        let state = self.state.lock().unwrap();
+        state
+            .entries
+            .iter()
+            .find(|e| e.picture_id == picture_id)
+            .and_then(|e| e.quarantine.clone())
+    }
+
+    /// Records that a picture has been scanned for faces.
+    /// With Sqlite, face scans are recorded by the people repository.
+    pub fn add_face_scan(&mut self, picture_id: &PictureId) -> Result<()> {
+        self.update(picture_id, |entry| {
+            entry.is_face_scanned = true;
+        })
+    }
+
+    fn update<F>(&mut self, picture_id: &PictureId, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut Entry),
+    {
+        let mut state = self.state.lock().unwrap();
+        match state
+            .entries
+            .iter_mut()
+            .find(|e| e.picture_id == *picture_id)
+        {
+            Some(entry) => {
+                f(entry);
+                Ok(())
+            }
+            None => bail!("No picture with ID {}", picture_id),
+        }
+    }
+
+    fn find<P>(&self, predicate: P) -> Vec<Picture>
+    where
+        P: Fn(&Entry) -> bool,
+    {
+        let state = self.state.lock().unwrap();
+        let mut pics: Vec<Picture> = state
+            .entries
+            .iter()
+            .filter(|e| !e.is_broken)
+            .filter(|e| predicate(e))
+            .map(|e| e.to_picture())
+            .collect();
+        pics.sort_by_key(|p| p.ordering_ts);
+        pics
+    }
+}
+
+impl PictureStore for MemoryRepository {
+    fn add_all(&mut self, pics: &Vec<ScannedFile>) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        for pic in pics {
+            if !pic.path.starts_with(&self.library_base_path) {
+                bail!("{:?} is not in {:?}", pic.path, self.library_base_path);
+            }
+
+            let existing = state
+                .entries
+                .iter()
+                .position(|e| e.scanned.path == pic.path);
+            if let Some(index) = existing {
+                state.entries[index].scanned = pic.clone();
+            } else {
+                state.next_id += 1;
+                let picture_id = PictureId::new(state.next_id);
+                state.entries.push(Entry {
+                    picture_id,
+                    scanned: pic.clone(),
+                    metadata: None,
+                    metadata_version: 0,
+                    thumbnail_path: None,
+                    motion_photo_version: 0,
+                    motion_photo: None,
+                    is_broken: false,
+                    quarantine: None,
+                    is_face_scanned: false,
+                    added_at: Utc::now(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn add_metadatas(&mut self, pics: Vec<(PictureId, Metadata)>) -> Result<()> {
+        for (picture_id, metadata) in pics {
+            self.update(&picture_id, |entry| {
+                entry.metadata = Some(metadata);
+                entry.metadata_version = metadata::VERSION;
+                if entry
+                    .quarantine
+                    .as_ref()
+                    .is_some_and(|(stage, _)| *stage == quarantine::Stage::Metadata)
+                {
+                    entry.quarantine = None;
+                }
+            })?;
+        }
+        Ok(())
+    }
+
+    fn add_thumbnail(&mut self, picture_id: &PictureId, thumbnail_path: &Path) -> Result<()> {
+        self.update(picture_id, |entry| {
+            entry.thumbnail_path = Some(thumbnail_path.into());
+            entry.is_broken = false;
+            entry.quarantine = None;
+        })
+    }
+
+    fn add_motion_photo_video(
+        &mut self,
+        picture_id: &PictureId,
+        video: Option<MotionPhotoVideo>,
+    ) -> Result<()> {
+        self.update(picture_id, |entry| {
+            entry.motion_photo = video;
+            entry.motion_photo_version = motion_photo::VERSION;
+        })
+    }
+
+    fn mark_broken(
+        &mut self,
+        picture_id: &PictureId,
+        stage: quarantine::Stage,
+        error: &str,
+    ) -> Result<()> {
+        self.update(picture_id, |entry| {
+            entry.is_broken = true;
+            entry.quarantine = Some((stage, error.into()));
+        })
+    }
+
+    fn quarantine(
+        &mut self,
+        picture_id: &PictureId,
+        stage: quarantine::Stage,
+        error: &str,
+    ) -> Result<()> {
+        self.update(picture_id, |entry| {
+            entry.quarantine = Some((stage, error.into()));
+        })
+    }
+
+    fn remove(&mut self, picture_id: PictureId) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.entries.retain(|e| e.picture_id != picture_id);
+        Ok(())
+    }
+
+    fn all(&self) -> Result<Vec<Picture>> {
+        Ok(self.find(|_| true))
+    }
+
+    fn find_need_metadata_update(&self) -> Result<Vec<Picture>> {
+        Ok(self.find(|e| e.metadata_version < metadata::VERSION))
+    }
+
+    fn find_need_motion_photo_extract(&self) -> Result<Vec<Picture>> {
+        Ok(self.find(|e| e.motion_photo_version < motion_photo::VERSION))
+    }
+
+    fn find_files_to_cleanup(&self, picture_id: PictureId) -> Result<Vec<PathBuf>> {
+        let state = self.state.lock().unwrap();
+        let paths = state
+            .entries
+            .iter()
+            .filter(|e| e.picture_id == picture_id)
+            .flat_map(|e| {
+                let video = e.motion_photo.as_ref();
+                [
+                    e.thumbnail_path.clone(),
+                    video.map(|v| v.path.clone()),
+                    video.and_then(|v| v.transcoded_path.clone()),
+                ]
+            })
+            .flatten()
+            .collect();
+        Ok(paths)
+    }
+
+    fn find_need_face_scan(&self) -> Result<Vec<(PictureId, PathBuf)>> {
+        let mut pics = self.find(|e| !e.is_face_scanned);
+        pics.reverse();
+        Ok(pics.into_iter().map(|p| (p.picture_id, p.path)).collect())
+    }
+
+    fn get_picture_path(&self, picture_id: PictureId) -> Result<Option<PathBuf>> {
+        let state = self.state.lock().unwrap();
+        let path = state
+            .entries
+            .iter()
+            .find(|e| e.picture_id == picture_id)
+            .map(|e| e.scanned.path.clone());
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scanned(path: &str) -> ScannedFile {
+        ScannedFile {
+            path: PathBuf::from(path),
+            fs_created_at: None,
+            fs_modified_at: None,
+            fs_file_size_bytes: 0,
+        }
+    }
+
+    #[test]
+    fn test_add_all_is_idempotent() {
+        let mut repo = MemoryRepository::new(Path::new("/pics"));
+        let pics = vec![scanned("/pics/a.jpg"), scanned("/pics/b.jpg")];
+        repo.add_all(&pics).unwrap();
+        repo.add_all(&pics).unwrap();
+        assert_eq!(2, repo.all().unwrap().len());
+    }
+
+    #[test]
+    fn test_add_all_outside_library() {
+        let mut repo = MemoryRepository::new(Path::new("/pics"));
+        assert!(repo.add_all(&vec![scanned("/elsewhere/a.jpg")]).is_err());
+    }
+
+    #[test]
+    fn test_metadata_update() {
+        let mut repo = MemoryRepository::new(Path::new("/pics"));
+        repo.add_all(&vec![scanned("/pics/a.jpg")]).unwrap();
+
+        let pics = repo.find_need_metadata_update().unwrap();
+        assert_eq!(1, pics.len());
+
+        repo.add_metadatas(vec![(pics[0].picture_id, Metadata::default())])
+            .unwrap();
+        assert!(repo.find_need_metadata_update().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_mark_broken() {
+        let mut repo = MemoryRepository::new(Path::new("/pics"));
+        repo.add_all(&vec![scanned("/pics/a.jpg")]).unwrap();
+        let picture_id = repo.all().unwrap()[0].picture_id;
+
+        repo.mark_broken(&picture_id, quarantine::Stage::Metadata, "bad")
+            .unwrap();
+
+        assert!(repo.all().unwrap().is_empty());
+        assert!(repo.find_need_metadata_update().unwrap().is_empty());
+        assert_eq!(
+            Some((quarantine::Stage::Metadata, "bad".to_string())),
+            repo.quarantined(picture_id)
+        );
+    }
+
+    #[test]
+    fn test_quarantine_keeps_picture() {
+        let mut repo = MemoryRepository::new(Path::new("/pics"));
+        repo.add_all(&vec![scanned("/pics/a.jpg")]).unwrap();
+        let picture_id = repo.all().unwrap()[0].picture_id;
+
+        repo.quarantine(&picture_id, quarantine::Stage::Metadata, "bad")
+            .unwrap();
+
+        assert_eq!(1, repo.all().unwrap().len());
+        assert_eq!(1, repo.find_need_metadata_update().unwrap().len());
+
+        repo.add_metadatas(vec![(picture_id, Metadata::default())])
+            .unwrap();
+        assert_eq!(None, repo.quarantined(picture_id));
+    }
+
+    #[test]
+    fn test_thumbnail_releases_from_quarantine() {
+        let mut repo = MemoryRepository::new(Path::new("/pics"));
+        repo.add_all(&vec![scanned("/pics/a.jpg")]).unwrap();
+        let picture_id = repo.all().unwrap()[0].picture_id;
+
+        repo.mark_broken(&picture_id, quarantine::Stage::Thumbnail, "bad")
+            .unwrap();
+        repo.add_thumbnail(&picture_id, Path::new("/cache/a.avif"))
+            .unwrap();
+
+        assert_eq!(1, repo.all().unwrap().len());
+        assert_eq!(None, repo.quarantined(picture_id));
+    }
+
+    #[test]
+    fn test_face_scan() {
+        let mut repo = MemoryRepository::new(Path::new("/pics"));
+        repo.add_all(&vec![scanned("/pics/a.jpg"), scanned("/pics/b.jpg")])
+            .unwrap();
+        let picture_id = repo.all().unwrap()[0].picture_id;
+
+        repo.add_face_scan(&picture_id).unwrap();
+
+        let pics = repo.find_need_face_scan().unwrap();
+        assert_eq!(1, pics.len());
+        assert_ne!(picture_id, pics[0].0);
+    }
+
+    #[test]
+    fn test_order_without_timestamps_is_stable() {
+        let mut repo = MemoryRepository::new(Path::new("/pics"));
+        repo.add_all(&vec![scanned("/pics/a.jpg"), scanned("/pics/b.jpg")])
+            .unwrap();
+        let order = |repo: &MemoryRepository| -> Vec<(PictureId, DateTime<Utc>)> {
+            repo.all()
+                .unwrap()
+                .into_iter()
+                .map(|p| (p.picture_id, p.ordering_ts))
+                .collect()
+        };
+        assert_eq!(order(&repo), order(&repo));
+    }
+
+    #[test]
+    fn test_clones_share_state() {
+        let mut repo = MemoryRepository::new(Path::new("/pics"));
+        let other = repo.clone();
+        repo.add_all(&vec![scanned("/pics/a.jpg")]).unwrap();
+        assert_eq!(1, other.all().unwrap().len());
+    }
+
+    #[test]
+    fn test_files_to_cleanup() {
+        let mut repo = MemoryRepository::new(Path::new("/pics"));
+        repo.add_all(&vec![scanned("/pics/a.jpg")]).unwrap();
+        let picture_id = repo.all().unwrap()[0].picture_id;
+
+        repo.add_thumbnail(&picture_id, Path::new("/cache/a.avif"))
+            .unwrap();
+
+        let paths = repo.find_files_to_cleanup(picture_id).unwrap();
+        assert_eq!(vec![PathBuf::from("/cache/a.avif")], paths);
+
+        repo.remove(picture_id).unwrap();
+        assert!(repo.get_picture_path(picture_id).unwrap().is_none());
+    }
+}
@@ -3,17 +3,21 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 pub mod gps;
+pub mod memory;
 pub mod metadata;
 pub mod model;
 pub mod motion_photo;
 pub mod repo;
 pub mod scanner;
+pub mod store;
 pub mod thumbnail;
 
 pub use model::PictureId;
 
+pub use memory::MemoryRepository;
 pub use model::Metadata;
 pub use motion_photo::MotionPhotoExtractor;
 pub use repo::Repository;
 pub use scanner::Scanner;
+pub use store::PictureStore;
 pub use thumbnail::Thumbnailer;
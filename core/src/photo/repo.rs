@@ -9,7 +9,8 @@ use super::model::MotionPhotoVideo;
 use super::motion_photo;
 use super::Metadata;
 use crate::path_encoding;
-use anyhow::{bail, Result};
+use crate::quarantine;
+use anyhow::Result;
 use rusqlite;
 use rusqlite::params;
 use rusqlite::Row;
@@ -41,10 +42,8 @@ impl Repository {
         data_dir_base_path: &Path,
         con: Arc<Mutex<rusqlite::Connection>>,
     ) -> Result<Repository> {
-        if !library_base_path.is_dir() {
-            bail!("{:?} is not a directory", library_base_path);
-        }
-
+        // Library directory isn't checked because it might be on a network share
+        // that is currently offline. Pictures are still viewable from the cache.
         let library_base_path = PathBuf::from(library_base_path);
         let cache_dir_base_path = PathBuf::from(cache_dir_base_path);
         let data_dir_base_path = PathBuf::from(data_dir_base_path);
@@ -89,7 +88,17 @@ impl Repository {
                 ",
             )?;
 
+            // Metadata is readable again, so no longer a problem file.
+            let mut delete_quarantine = tx.prepare_cached(
+                "DELETE FROM pictures_quarantine WHERE picture_id = ?1 AND stage = ?2",
+            )?;
+
             for (picture_id, metadata) in pics {
+                delete_quarantine.execute(params![
+                    picture_id.id(),
+                    quarantine::Stage::Metadata.as_ref()
+                ])?;
+
                 update_pictures.execute(params![
                     picture_id.id(),
                     metadata::VERSION,
@@ -137,13 +146,25 @@ impl Repository {
                 picture_id.id(),
                 thumbnail_path.as_ref().map(|p| p.to_str()),
             ])?;
+
+            // File is readable again, so no longer a problem file.
+            let mut stmt =
+                tx.prepare_cached("DELETE FROM pictures_quarantine WHERE picture_id = ?1")?;
+            stmt.execute([picture_id.id()])?;
         }
 
         tx.commit()?;
         Ok(())
     }
 
-    pub fn mark_broken(&mut self, picture_id: &PictureId) -> Result<()> {
+    /// Marks a picture as broken and quarantines it so it won't be processed again
+    /// until the user retries it.
+    pub fn mark_broken(
+        &mut self,
+        picture_id: &PictureId,
+        stage: quarantine::Stage,
+        error: &str,
+    ) -> Result<()> {
         let mut con = self.con.lock().unwrap();
         let tx = con.transaction()?;
 
@@ -156,12 +177,52 @@ impl Repository {
             )?;
 
             stmt.execute(params![picture_id.id(),])?;
+
+            Repository::insert_quarantine(&tx, picture_id, stage, error)?;
         }
 
         tx.commit()?;
         Ok(())
     }
 
+    /// Quarantines a picture without marking it as broken, so it is still shown
+    /// and will be processed again by the next run of the background tasks.
+    /// For failures that might be temporary, such as a network share dropping out.
+    pub fn quarantine(
+        &mut self,
+        picture_id: &PictureId,
+        stage: quarantine::Stage,
+        error: &str,
+    ) -> Result<()> {
+        let con = self.con.lock().unwrap();
+        Repository::insert_quarantine(&con, picture_id, stage, error)
+    }
+
+    fn insert_quarantine(
+        con: &rusqlite::Connection,
+        picture_id: &PictureId,
+        stage: quarantine::Stage,
+        error: &str,
+    ) -> Result<()> {
+        let mut stmt = con.prepare_cached(
+            "INSERT INTO pictures_quarantine (
+                picture_id,
+                stage,
+                error,
+                quarantined_ts
+            ) VALUES (
+                ?1, ?2, ?3, CURRENT_TIMESTAMP
+            ) ON CONFLICT (picture_id) DO UPDATE SET
+                stage = ?2,
+                error = ?3,
+                quarantined_ts = CURRENT_TIMESTAMP
+            ",
+        )?;
+
+        stmt.execute(params![picture_id.id(), stage.as_ref(), error])?;
+        Ok(())
+    }
+
     /// Add all Pictures received from a vector.
     pub fn add_all(&mut self, pics: &Vec<ScannedFile>) -> Result<()> {
         let mut con = self.con.lock().unwrap();
@@ -243,6 +304,36 @@ impl Repository {
         Ok(result)
     }
 
+    /// Gets all pictures marked as broken. Broken pictures aren't shown, but must
+    /// still be forgotten when their files are deleted.
+    pub fn find_broken(&self) -> Result<Vec<Picture>> {
+        let con = self.con.lock().unwrap();
+        let mut stmt = con.prepare(
+            "SELECT
+                    pictures.picture_id,
+                    pictures.picture_path_b64,
+                    pictures.thumbnail_path,
+                    COALESCE(
+                        pictures.exif_created_ts,
+                        pictures.exif_modified_ts,
+                        pictures.fs_created_ts,
+                        pictures.fs_modified_ts,
+                        CURRENT_TIMESTAMP
+                      ) AS ordering_ts,
+                    pictures.is_selfie
+                FROM pictures
+                WHERE COALESCE(is_broken, FALSE) IS TRUE
+                ORDER BY ordering_ts ASC",
+        )?;
+
+        let result = stmt
+            .query_map([], |row| self.to_picture(row))?
+            .flatten()
+            .collect();
+
+        Ok(result)
+    }
+
     /// Gets all pictures that haven't had their metadata extracted.
     /// Will return all pictures that are not broken and have a metadata version
     /// lower than the current metadata scanner.
@@ -14,6 +14,20 @@ use walkdir::WalkDir;
 
 // FIXME photos::Scanner and videos::Scanner are now broadly the same. Can they be consolidated?
 
+/// File name suffixes of supported picture types.
+const PICTURE_SUFFIXES: [&str; 10] = [
+    "avif", "exr", "heic", // not supported by image-rs
+    "jpeg", "jpg", "jxl", "png", "qoi", "tiff", "webp",
+];
+
+/// Is the file a supported picture type, going by its file name suffix?
+pub fn is_picture(path: &Path) -> bool {
+    path.extension()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_lowercase())
+        .is_some_and(|ext| PICTURE_SUFFIXES.contains(&ext.as_str()))
+}
+
 /// Scans a file system for pictures.
 #[derive(Debug, Clone)]
 pub struct Scanner {
@@ -23,7 +37,6 @@ pub struct Scanner {
 
 impl Scanner {
     pub fn build(scan_base: &Path) -> Result<Self> {
-        fs::create_dir_all(scan_base)?;
         let scan_base = PathBuf::from(scan_base);
         Ok(Self { scan_base })
     }
@@ -33,19 +46,6 @@ impl Scanner {
     where
         F: FnMut(ScannedFile),
     {
-        let picture_suffixes = [
-            String::from("avif"),
-            String::from("exr"),
-            String::from("heic"), // not supported by image-rs
-            String::from("jpeg"),
-            String::from("jpg"),
-            String::from("jxl"),
-            String::from("png"),
-            String::from("qoi"),
-            String::from("tiff"),
-            String::from("webp"),
-        ];
-
         WalkDir::new(&self.scan_base)
             .into_iter()
             .inspect(|x| {
@@ -55,15 +55,7 @@ impl Scanner {
             })
             .flatten() // skip files we failed to read
             .filter(|x| x.path().is_file()) // only process files
-            .filter(|x| {
-                // only process supported image types
-                let ext = x
-                    .path()
-                    .extension()
-                    .and_then(|s| s.to_str())
-                    .map(|s| s.to_lowercase());
-                picture_suffixes.contains(&ext.unwrap_or(String::from("not_an_image")))
-            })
+            .filter(|x| is_picture(x.path())) // only process supported image types
             .map(|x| self.scan_one(x.path())) // Get picture info for image path
             .inspect(|x| {
                 let _ = x
@@ -0,0 +1,134 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::model::{MotionPhotoVideo, Picture, PictureId, ScannedFile};
+use super::repo::Repository;
+use super::Metadata;
+use crate::quarantine;
+
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+/// Storage of picture metadata.
+/// Abstracts over the Sqlite backed `Repository` so that background tasks
+/// can run against other backends, such as the in-memory `MemoryRepository`.
+pub trait PictureStore: Send + Sync {
+    /// Add all pictures found by a scan. Existing pictures are updated.
+    fn add_all(&mut self, pics: &Vec<ScannedFile>) -> Result<()>;
+
+    fn add_metadatas(&mut self, pics: Vec<(PictureId, Metadata)>) -> Result<()>;
+
+    fn add_thumbnail(&mut self, picture_id: &PictureId, thumbnail_path: &Path) -> Result<()>;
+
+    fn add_motion_photo_video(
+        &mut self,
+        picture_id: &PictureId,
+        video: Option<MotionPhotoVideo>,
+    ) -> Result<()>;
+
+    /// Marks a picture as broken and quarantines it so it won't be processed again
+    /// until the user retries it.
+    fn mark_broken(
+        &mut self,
+        picture_id: &PictureId,
+        stage: quarantine::Stage,
+        error: &str,
+    ) -> Result<()>;
+
+    /// Quarantines a picture without marking it as broken, so it is still shown
+    /// and will be processed again by the next run of the background tasks.
+    fn quarantine(
+        &mut self,
+        picture_id: &PictureId,
+        stage: quarantine::Stage,
+        error: &str,
+    ) -> Result<()>;
+
+    fn remove(&mut self, picture_id: PictureId) -> Result<()>;
+
+    /// Gets all pictures, in ascending order of ordering timestamp.
+    fn all(&self) -> Result<Vec<Picture>>;
+
+    /// Gets all pictures that haven't had their metadata extracted.
+    fn find_need_metadata_update(&self) -> Result<Vec<Picture>>;
+
+    /// Gets all pictures that haven't been inspected for containing a motion photo.
+    fn find_need_motion_photo_extract(&self) -> Result<Vec<Picture>>;
+
+    /// Gets paths of files to delete when a picture is no longer present.
+    fn find_files_to_cleanup(&self, picture_id: PictureId) -> Result<Vec<PathBuf>>;
+
+    /// Gets all pictures that haven't been scanned for faces.
+    fn find_need_face_scan(&self) -> Result<Vec<(PictureId, PathBuf)>>;
+
+    fn get_picture_path(&self, picture_id: PictureId) -> Result<Option<PathBuf>>;
+}
+
+impl PictureStore for Repository {
+    fn add_all(&mut self, pics: &Vec<ScannedFile>) -> Result<()> {
+        Repository::add_all(self, pics)
+    }
+
+    fn add_metadatas(&mut self, pics: Vec<(PictureId, Metadata)>) -> Result<()> {
+        Repository::add_metadatas(self, pics)
+    }
+
+    fn add_thumbnail(&mut self, picture_id: &PictureId, thumbnail_path: &Path) -> Result<()> {
+        Repository::add_thumbnail(self, picture_id, thumbnail_path)
+    }
+
+    fn add_motion_photo_video(
+        &mut self,
+        picture_id: &PictureId,
+        video: Option<MotionPhotoVideo>,
+    ) -> Result<()> {
+        Repository::add_motion_photo_video(self, picture_id, video)
+    }
+
+    fn mark_broken(
+        &mut self,
+        picture_id: &PictureId,
+        stage: quarantine::Stage,
+        error: &str,
+    ) -> Result<()> {
+        Repository::mark_broken(self, picture_id, stage, error)
+    }
+
+    fn quarantine(
+        &mut self,
+        picture_id: &PictureId,
+        stage: quarantine::Stage,
+        error: &str,
+    ) -> Result<()> {
+        Repository::quarantine(self, picture_id, stage, error)
+    }
+
+    fn remove(&mut self, picture_id: PictureId) -> Result<()> {
+        Repository::remove(self, picture_id)
+    }
+
+    fn all(&self) -> Result<Vec<Picture>> {
+        Repository::all(self)
+    }
+
+    fn find_need_metadata_update(&self) -> Result<Vec<Picture>> {
+        Repository::find_need_metadata_update(self)
+    }
+
+    fn find_need_motion_photo_extract(&self) -> Result<Vec<Picture>> {
+        Repository::find_need_motion_photo_extract(self)
+    }
+
+    fn find_files_to_cleanup(&self, picture_id: PictureId) -> Result<Vec<PathBuf>> {
+        Repository::find_files_to_cleanup(self, picture_id)
+    }
+
+    fn find_need_face_scan(&self) -> Result<Vec<(PictureId, PathBuf)>> {
+        Repository::find_need_face_scan(self)
+    }
+
+    fn get_picture_path(&self, picture_id: PictureId) -> Result<Option<PathBuf>> {
+        Repository::get_picture_path(self, picture_id)
+    }
+}
@@ -0,0 +1,44 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::{PictureId, VideoId};
+use chrono::{DateTime, Utc};
+use std::path::PathBuf;
+use strum::{AsRefStr, EnumString};
+
+/// Processing stage at which a file failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AsRefStr, EnumString)]
+pub enum Stage {
+    /// Extracting EXIF or video container metadata.
+    Metadata,
+
+    /// Decoding the file to generate a thumbnail.
+    Thumbnail,
+
+    /// Extracting the video from a motion photo.
+    MotionPhoto,
+}
+
+/// A picture or video that failed processing and won't be processed again
+/// until the user asks for it to be retried.
+#[derive(Debug, Clone)]
+pub struct QuarantinedFile {
+    /// Set if the quarantined file is a picture.
+    pub picture_id: Option<PictureId>,
+
+    /// Set if the quarantined file is a video.
+    pub video_id: Option<VideoId>,
+
+    /// Full path to file.
+    pub path: PathBuf,
+
+    /// Stage that failed.
+    pub stage: Stage,
+
+    /// Error message from failure.
+    pub error: String,
+
+    /// When the file was quarantined.
+    pub quarantined_at: DateTime<Utc>,
+}
@@ -0,0 +1,326 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::model::{QuarantinedFile, Stage};
+use crate::path_encoding;
+use crate::{PictureId, VideoId};
+
+use anyhow::*;
+use rusqlite;
+use rusqlite::Row;
+use std::path::{Path, PathBuf};
+use std::result::Result::Ok;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+/// Repository of pictures and videos that failed processing.
+/// Repository is backed by a Sqlite database.
+#[derive(Debug, Clone)]
+pub struct Repository {
+    /// Base path to picture library on file system
+    library_base_path: PathBuf,
+
+    /// Connection to backing Sqlite database.
+    con: Arc<Mutex<rusqlite::Connection>>,
+}
+
+impl Repository {
+    pub fn open(
+        library_base_path: &Path,
+        con: Arc<Mutex<rusqlite::Connection>>,
+    ) -> Result<Repository> {
+        let repo = Repository {
+            library_base_path: PathBuf::from(library_base_path),
+            con,
+        };
+        Ok(repo)
+    }
+
+    /// Gets all quarantined files, most recently quarantined first.
+    pub fn all(&self) -> Result<Vec<QuarantinedFile>> {
+        let con = self.con.lock().unwrap();
+        let mut stmt = con.prepare(
+            "SELECT
+                    picture_id,
+                    video_id,
+                    path_b64,
+                    stage,
+                    error,
+                    quarantined_ts
+                FROM quarantine
+                ORDER BY quarantined_ts DESC",
+        )?;
+
+        let result = stmt
+            .query_map([], |row| self.to_quarantined_file(row))?
+            .flatten()
+            .collect();
+
+        Ok(result)
+    }
+
+    /// Releases a picture from quarantine so it will be processed again by
+    /// the next run of the background tasks.
+    pub fn retry_picture(&mut self, picture_id: PictureId) -> Result<()> {
+        let mut con = self.con.lock().unwrap();
+        let tx = con.transaction()?;
+
+        {
+            let mut stmt = tx.prepare_cached(
+                "UPDATE pictures
+                SET
+                    is_broken = FALSE
+                WHERE picture_id = ?1",
+            )?;
+            stmt.execute([picture_id.id()])?;
+
+            let mut stmt =
+                tx.prepare_cached("DELETE FROM pictures_quarantine WHERE picture_id = ?1")?;
+            stmt.execute([picture_id.id()])?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Releases a video from quarantine so it will be processed again by
+    /// the next run of the background tasks.
+    pub fn retry_video(&mut self, video_id: VideoId) -> Result<()> {
+        let mut con = self.con.lock().unwrap();
+        let tx = con.transaction()?;
+
+        {
+            let mut stmt = tx.prepare_cached(
+                "UPDATE videos
+                SET
+                    is_broken = FALSE
+                WHERE video_id = ?1",
+            )?;
+            stmt.execute([video_id.id()])?;
+
+            let mut stmt =
+                tx.prepare_cached("DELETE FROM videos_quarantine WHERE video_id = ?1")?;
+            stmt.execute([video_id.id()])?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Removes a quarantined picture from the library without touching the file.
+    /// The picture is marked as broken, so it isn't shown or processed again
+    /// when the library is next scanned. It is forgotten once the file is deleted.
+    pub fn remove_picture(&mut self, picture_id: PictureId) -> Result<()> {
+        let mut con = self.con.lock().unwrap();
+        let tx = con.transaction()?;
+
+        {
+            let mut stmt = tx.prepare_cached(
+                "UPDATE pictures
+                SET
+                    is_broken = TRUE
+                WHERE picture_id = ?1",
+            )?;
+            stmt.execute([picture_id.id()])?;
+
+            let mut stmt =
+                tx.prepare_cached("DELETE FROM pictures_quarantine WHERE picture_id = ?1")?;
+            stmt.execute([picture_id.id()])?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Removes a quarantined video from the library without touching the file.
+    /// The video is marked as broken, so it isn't shown or processed again
+    /// when the library is next scanned. It is forgotten once the file is deleted.
+    pub fn remove_video(&mut self, video_id: VideoId) -> Result<()> {
+        let mut con = self.con.lock().unwrap();
+        let tx = con.transaction()?;
+
+        {
+            let mut stmt = tx.prepare_cached(
+                "UPDATE videos
+                SET
+                    is_broken = TRUE
+                WHERE video_id = ?1",
+            )?;
+            stmt.execute([video_id.id()])?;
+
+            let mut stmt =
+                tx.prepare_cached("DELETE FROM videos_quarantine WHERE video_id = ?1")?;
+            stmt.execute([video_id.id()])?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Forgets a quarantined picture whose file has been deleted.
+    pub fn delete_picture(&mut self, picture_id: PictureId) -> Result<()> {
+        let mut con = self.con.lock().unwrap();
+        let tx = con.transaction()?;
+
+        {
+            let mut stmt =
+                tx.prepare_cached("DELETE FROM pictures_quarantine WHERE picture_id = ?1")?;
+            stmt.execute([picture_id.id()])?;
+
+            let mut stmt = tx.prepare_cached("DELETE FROM pictures WHERE picture_id = ?1")?;
+            stmt.execute([picture_id.id()])?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Forgets a quarantined video whose file has been deleted.
+    pub fn delete_video(&mut self, video_id: VideoId) -> Result<()> {
+        let mut con = self.con.lock().unwrap();
+        let tx = con.transaction()?;
+
+        {
+            let mut stmt =
+                tx.prepare_cached("DELETE FROM videos_quarantine WHERE video_id = ?1")?;
+            stmt.execute([video_id.id()])?;
+
+            let mut stmt = tx.prepare_cached("DELETE FROM videos WHERE video_id = ?1")?;
+            stmt.execute([video_id.id()])?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn to_quarantined_file(&self, row: &Row<'_>) -> rusqlite::Result<QuarantinedFile> {
+        let picture_id: Option<PictureId> =
+            row.get::<_, Option<i64>>("picture_id")?.map(PictureId::new);
+
+        let video_id: Option<VideoId> = row.get::<_, Option<i64>>("video_id")?.map(VideoId::new);
+
+        let path: String = row.get("path_b64")?;
+        let path = path_encoding::from_base64(&path).map_err(|_| rusqlite::Error::InvalidQuery)?;
+        let path = self.library_base_path.join(path);
+
+        let stage: String = row.get("stage")?;
+        let stage = Stage::from_str(&stage).map_err(|_| rusqlite::Error::InvalidQuery)?;
+
+        let error = row.get("error")?;
+
+        let quarantined_at = row.get("quarantined_ts")?;
+
+        Ok(QuarantinedFile {
+            picture_id,
+            video_id,
+            path,
+            stage,
+            error,
+            quarantined_at,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database;
+    use crate::photo;
+
+    fn setup() -> (Arc<Mutex<rusqlite::Connection>>, Repository) {
+        let con = database::setup_in_memory().unwrap();
+        con.execute(
+            "INSERT INTO pictures (
+                picture_id,
+                picture_path_b64,
+                picture_path_lossy,
+                link_path_b64,
+                link_path_lossy,
+                is_broken
+            ) VALUES (1, 'YS5qcGc=', 'a.jpg', 'YQ==', 'a', TRUE)",
+            [],
+        )
+        .unwrap();
+        con.execute(
+            "INSERT INTO pictures_quarantine (picture_id, stage, error, quarantined_ts)
+            VALUES (1, 'Thumbnail', 'bad file', CURRENT_TIMESTAMP)",
+            [],
+        )
+        .unwrap();
+
+        let con = Arc::new(Mutex::new(con));
+        let repo = Repository::open(Path::new("/library"), con.clone()).unwrap();
+        (con, repo)
+    }
+
+    #[test]
+    fn remove_keeps_picture_broken() {
+        let (con, mut repo) = setup();
+        assert_eq!(1, repo.all().unwrap().len());
+
+        repo.remove_picture(PictureId::new(1)).unwrap();
+        assert!(repo.all().unwrap().is_empty());
+
+        let is_broken: bool = con
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT is_broken FROM pictures WHERE picture_id = 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(is_broken);
+    }
+
+    #[test]
+    fn removed_picture_is_found_for_cleaning() {
+        let (con, mut repo) = setup();
+
+        // Pictures with unreadable metadata are quarantined without being hidden.
+        con.lock()
+            .unwrap()
+            .execute("UPDATE pictures SET is_broken = FALSE", [])
+            .unwrap();
+
+        repo.remove_picture(PictureId::new(1)).unwrap();
+
+        let pictures = photo::Repository::open(
+            Path::new("/library"),
+            Path::new("/cache"),
+            Path::new("/data"),
+            con.clone(),
+        )
+        .unwrap();
+
+        assert!(pictures.all().unwrap().is_empty());
+
+        // Cleaning checks broken pictures, so the row is deleted with the file.
+        let broken = pictures.find_broken().unwrap();
+        assert_eq!(1, broken.len());
+        assert_eq!(PathBuf::from("/library/a.jpg"), broken[0].path);
+    }
+
+    #[test]
+    fn deleted_picture_leaves_no_quarantine_row() {
+        let (con, mut repo) = setup();
+
+        con.lock()
+            .unwrap()
+            .execute("DELETE FROM pictures WHERE picture_id = 1", [])
+            .unwrap();
+
+        let count: i64 = con
+            .lock()
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM pictures_quarantine", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(0, count);
+
+        // Deleting a missing picture isn't an error.
+        repo.delete_picture(PictureId::new(1)).unwrap();
+    }
+}
@@ -5,6 +5,7 @@
 use super::metadata;
 use super::Metadata;
 use crate::path_encoding;
+use crate::quarantine;
 use crate::video::model::{ScannedFile, Video, VideoId};
 use anyhow::*;
 use chrono::*;
@@ -71,13 +72,24 @@ impl Repository {
                 video_id.id(),
                 thumbnail_path.as_ref().map(|p| p.to_str()),
             ])?;
+
+            // File is readable again, so no longer a problem file.
+            let mut stmt = tx.prepare("DELETE FROM videos_quarantine WHERE video_id = ?1")?;
+            stmt.execute([video_id.id()])?;
         }
 
         tx.commit()?;
         Ok(())
     }
 
-    pub fn mark_broken(&mut self, video_id: &VideoId) -> Result<()> {
+    /// Marks a video as broken and quarantines it so it won't be processed again
+    /// until the user retries it.
+    pub fn mark_broken(
+        &mut self,
+        video_id: &VideoId,
+        stage: quarantine::Stage,
+        error: &str,
+    ) -> Result<()> {
         let mut con = self.con.lock().unwrap();
         let tx = con.transaction()?;
 
@@ -90,12 +102,52 @@ impl Repository {
             )?;
 
             stmt.execute(params![video_id.id(),])?;
+
+            Repository::insert_quarantine(&tx, video_id, stage, error)?;
         }
 
         tx.commit()?;
         Ok(())
     }
 
+    /// Quarantines a video without marking it as broken, so it is still shown
+    /// and will be processed again by the next run of the background tasks.
+    /// For failures that might be temporary, such as a network share dropping out.
+    pub fn quarantine(
+        &mut self,
+        video_id: &VideoId,
+        stage: quarantine::Stage,
+        error: &str,
+    ) -> Result<()> {
+        let con = self.con.lock().unwrap();
+        Repository::insert_quarantine(&con, video_id, stage, error)
+    }
+
+    fn insert_quarantine(
+        con: &rusqlite::Connection,
+        video_id: &VideoId,
+        stage: quarantine::Stage,
+        error: &str,
+    ) -> Result<()> {
+        let mut stmt = con.prepare(
+            "INSERT INTO videos_quarantine (
+                video_id,
+                stage,
+                error,
+                quarantined_ts
+            ) VALUES (
+                ?1, ?2, ?3, CURRENT_TIMESTAMP
+            ) ON CONFLICT (video_id) DO UPDATE SET
+                stage = ?2,
+                error = ?3,
+                quarantined_ts = CURRENT_TIMESTAMP
+            ",
+        )?;
+
+        stmt.execute(params![video_id.id(), stage.as_ref(), error])?;
+        Ok(())
+    }
+
     pub fn add_transcode(&mut self, video_id: VideoId, transcoded_path: &Path) -> Result<()> {
         let mut con = self.con.lock().unwrap();
         let tx = con.transaction()?;
@@ -138,7 +190,14 @@ impl Repository {
                 WHERE video_id = ?1",
             )?;
 
+            // Metadata is readable again, so no longer a problem file.
+            let mut delete_quarantine =
+                tx.prepare("DELETE FROM videos_quarantine WHERE video_id = ?1 AND stage = ?2")?;
+
             for (video_id, metadata) in vids {
+                delete_quarantine
+                    .execute(params![video_id.id(), quarantine::Stage::Metadata.as_ref()])?;
+
                 stmt.execute(params![
                     video_id.id(),
                     metadata::VERSION,
@@ -233,6 +292,34 @@ impl Repository {
         Ok(result)
     }
 
+    /// Gets all videos marked as broken. Broken videos aren't shown, but must
+    /// still be forgotten when their files are deleted.
+    pub fn find_broken(&self) -> Result<Vec<Video>> {
+        let con = self.con.lock().unwrap();
+        let mut stmt = con.prepare(
+            "SELECT
+                    video_id,
+                    video_path_b64,
+                    thumbnail_path,
+                    COALESCE(
+                        videos.stream_created_ts,
+                        videos.fs_created_ts,
+                        videos.fs_modified_ts,
+                        CURRENT_TIMESTAMP
+                    ) AS ordering_ts,
+                    duration_millis,
+                    video_codec,
+                    transcoded_path
+                FROM videos
+                WHERE COALESCE(is_broken, FALSE) IS TRUE
+                ORDER BY ordering_ts ASC",
+        )?;
+
+        let result = stmt.query_map([], |row| self.to_video(row))?;
+        let result = result.flatten().collect();
+        Ok(result)
+    }
+
     /// Gets all videos in the repository, in ascending order of modification timestamp.
     pub fn find_need_metadata_update(&self) -> Result<Vec<Video>> {
         let con = self.con.lock().unwrap();
@@ -13,6 +13,17 @@ use walkdir::WalkDir;
 
 // FIXME photos::Scanner and videos::Scanner are now broadly the same. Can they be consolidated?
 
+/// File name suffixes of supported video types.
+const VIDEO_SUFFIXES: [&str; 2] = ["mov", "mp4"];
+
+/// Is the file a supported video type, going by its file name suffix?
+pub fn is_video(path: &Path) -> bool {
+    path.extension()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_lowercase())
+        .is_some_and(|ext| VIDEO_SUFFIXES.contains(&ext.as_str()))
+}
+
 /// Scans a file system for videos.
 #[derive(Debug, Clone)]
 pub struct Scanner {
@@ -22,7 +33,6 @@ pub struct Scanner {
 
 impl Scanner {
     pub fn build(scan_base: &Path) -> Result<Scanner> {
-        fs::create_dir_all(scan_base)?;
         let scan_base = PathBuf::from(scan_base);
         Ok(Scanner { scan_base })
     }
@@ -32,8 +42,6 @@ impl Scanner {
     where
         F: FnMut(ScannedFile),
     {
-        let suffixes = [String::from("mov"), String::from("mp4")];
-
         WalkDir::new(&self.scan_base)
             .into_iter()
             .inspect(|x| {
@@ -43,15 +51,7 @@ impl Scanner {
             })
             .flatten() // skip files we failed to read
             .filter(|x| x.path().is_file()) // only process files
-            .filter(|x| {
-                // only process supported image types
-                let ext = x
-                    .path()
-                    .extension()
-                    .and_then(|s| s.to_str())
-                    .map(|s| s.to_lowercase());
-                suffixes.contains(&ext.unwrap_or(String::from("not_a_video")))
-            })
+            .filter(|x| is_video(x.path())) // only process supported video types
             .map(|x| self.scan_one(x.path())) // Get video info for path
             .inspect(|x| {
                 let _ = x
@@ -52,6 +52,91 @@ people-page-status-no-people =
   .description = { -app-name } will look for faces in new photos when launched.
   Name the people in your photos so { -app-name } can make an album for each person.
 
+# Title for page with a timeline of trips and events.
+# Attributes:
+#  .rename - tooltip for button to rename an event.
+events-page = Events
+  .rename = Rename Event
+
+# Status page shown for events page when no events are found.
+events-page-status-none =
+  .title = No events found
+  .description = { -app-name } groups photos and videos taken close together in time and place into events.
+
+# Details of an event on the events page.
+# Variables:
+#  days - (Number) number of days the event lasted.
+#  count - (Number) number of photos and videos in the event.
+events-page-subtitle = { $days ->
+   [one] { $days } day
+  *[other] { $days } days
+}, { $count ->
+   [one] { $count } item
+  *[other] { $count } items
+}
+
+# Automatic title for an event that has a place name.
+# Variables:
+#  place - (String) name of place, such as "Lisbon".
+#  date - (String) month and year the event started, such as "March 2023".
+event-title-place = { $place }, { $date }
+
+# Dialog to rename an event.
+event-rename-dialog =
+  .heading = Rename event?
+  .body = Leave the name empty to use the automatic title.
+  .cancel-button = Cancel
+  .rename-button = Rename
+
+# Title for page listing files that could not be processed.
+problem-files-page = Problem Files
+  .description = { -app-name } could not read these files. They will not be processed again unless you retry them.
+  .retry-all = Retry All
+  .retry = Retry
+  .remove = Remove from Library
+  .trash = Move to Trash
+  .open-folder = Open Containing Folder
+
+# Dialog to confirm moving a problem file to the trash.
+problem-files-trash-dialog =
+  .heading = Move to trash?
+  .cancel-button = Cancel
+  .trash-button = Move to Trash
+
+# Body of dialog to confirm moving a problem file to the trash.
+# Variables:
+#  name - (String) name of file to move to the trash.
+problem-files-trash-dialog-body = { $name } will be removed from your library and moved to the trash.
+
+# Status page shown when there are no problem files.
+problem-files-page-status-none =
+  .title = No problem files
+  .description = All files in your library were processed successfully.
+
+# Processing stage at which a problem file failed.
+problem-files-stage =
+  .metadata = Reading metadata
+  .thumbnail = Generating thumbnail
+  .motion-photo = Extracting motion photo
+
+# Page for searching photo titles, captions, and descriptions.
+# Attributes:
+#  .placeholder - placeholder text for search entry.
+#  .tooltip - tooltip text for button that opens the search page.
+search-album = Search
+  .placeholder = Search titles and captions
+  .tooltip = Search
+
+# Status page shown on search page before anything has been searched for.
+search-album-status-empty =
+  .title = Search your photos
+  .description = Find photos by the words in their titles, captions, and descriptions.
+
+# Status page shown on search page when nothing matches the search.
+search-album-status-no-results =
+  .title = No results found
+  .description = Try different words, or add captions to photos from the information sidebar.
+
 ## Thumbnail decorations
 
 # Label on month album thumbnails.
@@ -152,8 +237,25 @@ viewer-error-missing-file = Cannot display file because it is missing:
 # be raised.
 viewer-error-missing-path = File path not present in database
 
+# Viewer is showing a thumbnail instead of the full picture because the library
+# folder is on a network share that isn't available.
+# Attributes:
+#  .tooltip - Tooltip for offline badge.
+viewer-offline =
+  .tooltip = Showing a preview until the library is available again.
+
 ## Photo/Video Information Sidebar
 
+# User-editable descriptive text for a photo.
+# Attributes:
+#  .title - short title of photo.
+#  .caption - one line caption for photo.
+#  .description - longer description of photo.
+infobar-caption = Caption
+  .title = Title
+  .caption = Caption
+  .description = Description
+
 # Name of containing folder of photo or video being viewed.
 # Attributes:
 #  .tooltip - tooltip text for open folder action button.
@@ -258,6 +360,41 @@ prefs-library-section-pictures-dir =
   .title = Pictures Directory
   .tooltip = Choose pictures directory.
 
+# Switch to keep indexing the library when the window is closed.
+# Attributes:
+#  .reason - Shown by the desktop when asking the user for permission.
+prefs-library-section-run-in-background =
+  .title = Run in Background
+  .subtitle = Keep the library up to date when the window is closed and start when you log in.
+  .reason = Keep your photo library up to date in the background.
+
+# Title of section of preferences for importing files dropped on the window.
+prefs-import-section =
+  .title = Import
+  .description = Choose what happens to pictures and videos dragged onto { -app-name }.
+
+# Switch to put imported files into folders with a template.
+prefs-import-section-organize =
+  .title = Organize Imports
+  .subtitle = Put imported files into folders by the date they were taken. Otherwise files go in the top of the pictures directory.
+
+# Text entry for template for folders and file names of imported files.
+# Placeholders in braces must not be translated.
+# Attributes:
+#  .tooltip - Explains the placeholders.
+prefs-import-section-template =
+  .title = Folder Template
+  .tooltip = Placeholders are {"{year}"}, {"{month}"}, {"{day}"}, {"{filename}"}, {"{stem}"}, and {"{extension}"}. For example, {"{year}/{month}/{filename}"}.
+
+# Drop-down menu to copy or move imported files.
+# Attributes:
+#  .copy - Keep the original files.
+#  .move - Delete the original files once they are in the library.
+prefs-import-section-transfer =
+  .title = Import By
+  .copy = Copying Files
+  .move = Moving Files
+
 ## Progress bar for background tasks
 
 # Extracting details from photo EXIF data
@@ -335,6 +472,161 @@ banner-button-stop =
 # Background tasks are in the process of being stopped
 banner-stopping = Stopping tasks...
 
+# Badge shown in the header bar when the library folder can't be read,
+# such as when it is on a network share that isn't mounted.
+# Attributes:
+#  .tooltip - Tooltip explaining the badge.
+library-offline-badge = Offline
+  .tooltip = The library folder is unavailable. Showing saved previews until it is back.
+
+## Desktop notifications
+
+# Notification sent when background tasks have finished processing the library
+# and the Fotema window isn't focused.
+# Attributes:
+#  .title - Notification title.
+#  .open - Button to show the Fotema window.
+notification-library-ready =
+  .title = Your library is ready
+  .open = Open { -app-name }
+
+# Body of notification sent when the library is ready.
+# Variables:
+#  count - (Number) number of photos and videos in the library.
+notification-library-ready-body = { $count ->
+   [one] Your library has { $count } photo or video
+  *[other] Your library has { $count } photos and videos
+}
+
+## Memory movies
+
+# Menu for creating a slideshow movie from pictures.
+# Attributes:
+#  .tooltip - Tooltip for menu button.
+#  .album - Menu item to create a movie of the album being viewed.
+#  .on-this-day - Menu item to create a movie of pictures taken on this day in previous years.
+memory-movie-menu =
+  .tooltip = Create Memory Movie
+  .album = Movie of This Album
+  .on-this-day = Movie of This Day in Past Years
+
+# Dialog showing progress of creating a movie.
+# Attributes:
+#  .save - Button to copy movie into the pictures library.
+#  .share - Button to send movie to another app.
+memory-movie-dialog = Memory Movie
+  .save = Save to Library
+  .share = Share
+
+# Shown while a movie is being created.
+# Variables:
+#  count - (Number) number of pictures in the movie.
+memory-movie-rendering = { $count ->
+   [one] Creating a movie of { $count } picture…
+  *[other] Creating a movie of { $count } pictures…
+}
+
+# Shown when a movie has been created.
+memory-movie-rendered = Your movie is ready.
+
+# Shown when a movie has been saved to the pictures library.
+# Variables:
+#  folder - (String) name of folder in pictures library.
+memory-movie-saved = Saved to the { $folder } folder of your library.
+
+# Shown when there are no pictures to make a movie from.
+memory-movie-no-pictures = There are no pictures to make a movie from.
+
+# Shown when a movie could not be created.
+memory-movie-failed = Sorry, the movie could not be created.
+
+# Shown when saving a movie while the pictures directory is on a network share
+# that isn't available.
+memory-movie-offline = Your library is offline. Reconnect to save the movie.
+
+## Import
+
+# Dialog previewing where files dragged onto the window will go in the library.
+# Attributes:
+#  .import - Button to copy or move files into the library.
+import-dialog = Import
+  .import = Import
+
+# Shown while working out where files will go.
+import-dialog-planning = Looking for pictures and videos…
+
+# Shown when nothing that was dropped is a picture or video.
+import-dialog-no-files = There are no pictures or videos to import.
+
+# Shown when the pictures directory is on a network share that isn't available.
+import-dialog-offline = Your library is offline. Reconnect to import pictures and videos.
+
+# Shown when the folder template in preferences is not valid.
+import-dialog-invalid-template = The folder template in preferences is not valid.
+
+# Preview of files to copy into the library.
+# Variables:
+#  count - (Number) number of files that will be copied.
+import-dialog-copy-preview = { $count ->
+   [one] { $count } file will be copied into your library.
+  *[other] { $count } files will be copied into your library.
+}
+
+# Preview of files to move into the library.
+# Variables:
+#  count - (Number) number of files that will be moved.
+import-dialog-move-preview = { $count ->
+   [one] { $count } file will be moved into your library.
+  *[other] { $count } files will be moved into your library.
+}
+
+# Shown while files are being copied or moved.
+# Variables:
+#  count - (Number) number of files being imported.
+import-dialog-importing = { $count ->
+   [one] Importing { $count } file…
+  *[other] Importing { $count } files…
+}
+
+# Shown when all files have been imported.
+# Variables:
+#  count - (Number) number of files imported.
+import-dialog-done = { $count ->
+   [one] Imported { $count } file.
+  *[other] Imported { $count } files.
+}
+
+# Shown when some files could not be imported.
+# Variables:
+#  count - (Number) number of files that failed.
+import-dialog-failed = { $count ->
+   [one] Sorry, { $count } file could not be imported.
+  *[other] Sorry, { $count } files could not be imported.
+}
+
+# Shown when some files were imported and others could not be.
+# Variables:
+#  imported - (Number) number of files imported.
+#  failed - (Number) number of files that failed.
+import-dialog-partial = { $imported ->
+   [one] Imported { $imported } file
+  *[other] Imported { $imported } files
+}, but { $failed ->
+   [one] { $failed } file could not be imported.
+  *[other] { $failed } files could not be imported.
+}
+
+# Subtitle for a file that won't be imported because it is already in the library.
+import-dialog-duplicate = Already in your library
+
+# Last row of a long preview.
+# Variables:
+#  count - (Number) number of files not listed.
+import-dialog-more = { $count ->
+   [one] And { $count } more file
+  *[other] And { $count } more files
+}
+
 ## Primary menu
 
 # The "hamburger" menu on the main app navigation sidebar.
@@ -9,8 +9,11 @@ use relm4::{
     component::{AsyncComponent, AsyncComponentController},
     gtk,
     gtk::{
-        gio, glib,
-        prelude::{ApplicationExt, ButtonExt, GtkWindowExt, OrientableExt, SettingsExt, WidgetExt},
+        gdk, gio, glib,
+        prelude::{
+            ApplicationExt, BoxExt, ButtonExt, FileExt, GtkApplicationExt, GtkWindowExt,
+            OrientableExt, SettingsExt, StaticType, VolumeMonitorExt, WidgetExt,
+        },
     },
     main_application,
     prelude::AsyncController,
@@ -23,7 +26,11 @@ use crate::adaptive;
 use crate::config::{APP_ID, PROFILE};
 use crate::fl;
 
+use fotema_core::caption;
 use fotema_core::database;
+use fotema_core::event;
+use fotema_core::import;
+use fotema_core::network_share::{self, Availability};
 use fotema_core::path_encoding;
 use fotema_core::people;
 use fotema_core::PictureId;
@@ -34,6 +41,7 @@ use h3o::CellIndex;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use anyhow::*;
 
@@ -49,19 +57,28 @@ use self::components::{
         album::{Album, AlbumInput, AlbumOutput},
         album_filter::AlbumFilter,
         album_sort::AlbumSort,
+        events_album::{EventsAlbum, EventsAlbumInput, EventsAlbumOutput},
         folders_album::{FoldersAlbum, FoldersAlbumInput, FoldersAlbumOutput},
         people_album::{PeopleAlbum, PeopleAlbumInput, PeopleAlbumOutput},
         person_album::{PersonAlbum, PersonAlbumInput, PersonAlbumOutput},
         places_album::{PlacesAlbum, PlacesAlbumInput, PlacesAlbumOutput},
+        search_album::{SearchAlbum, SearchAlbumInput, SearchAlbumOutput},
     },
+    import::{ImportDialog, ImportInput, ImportOutput},
     library::{Library, LibraryInput, LibraryOutput},
+    memory_movie::{MemoryMovie, MemoryMovieInput, MemoryMovieOutput, MemoryMovieSource},
     onboard::{Onboard, OnboardOutput},
     preferences::{PreferencesDialog, PreferencesInput},
+    problem_files::{ProblemFiles, ProblemFilesInput, ProblemFilesOutput},
     viewer::view_nav::{ViewNav, ViewNavInput, ViewNavOutput},
 };
 
 mod background;
 
+mod library_watcher;
+
+mod portal;
+
 use self::background::bootstrap::{
     Bootstrap, BootstrapInput, BootstrapOutput, MediaType, TaskName,
 };
@@ -69,6 +86,16 @@ use self::background::bootstrap::{
 use self::components::progress_monitor::ProgressMonitor;
 use self::components::progress_panel::ProgressPanel;
 
+use self::library_watcher::LibraryWatcher;
+
+/// How often to check if an offline library has come back, in case
+/// the share returns without a mount event, such as an NFS server restarting.
+const OFFLINE_RETRY_INTERVAL_SECONDS: u32 = 60;
+
+/// Indexing that takes at least this long is worth a notification when it completes.
+/// Quicker rescans that pick up a few new files are not.
+const LONG_INDEXING_DURATION: Duration = Duration::from_secs(2 * 60);
+
 /// Name of a view that can be displayed
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq, EnumString, IntoStaticStr)]
 pub enum ViewName {
@@ -84,8 +111,11 @@ pub enum ViewName {
     Folder,
     People,
     Person,
+    Search,
     Places,
+    Events,
     Selfies,
+    ProblemFiles,
 }
 
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq, EnumString, AsRefStr, FromRepr)]
@@ -118,7 +148,26 @@ pub struct Settings {
     pub is_onboarding_complete: bool,
 
     /// Base path of pictures directory.
+    /// Will be a Documents portal path if access was granted via the FileChooser portal.
     pub pictures_base_dir: PathBuf,
+
+    /// Documents portal ID for pictures directory.
+    pub pictures_base_dir_doc_id: Option<String>,
+
+    /// Path of pictures directory on the host. For display only.
+    pub pictures_base_dir_host: Option<PathBuf>,
+
+    /// Keep running to index the library after the window is closed.
+    pub run_in_background: bool,
+
+    /// Put imported files into folders following the import template.
+    pub organize_imports: bool,
+
+    /// Folder template for organizing imported files, such as `{year}/{month}/{filename}`.
+    pub import_template: String,
+
+    /// Copy or move imported files into the library.
+    pub import_transfer: import::Transfer,
 }
 
 /// Active settings
@@ -131,6 +180,11 @@ type SettingsState = Arc<relm4::SharedState<Settings>>;
 /// to kill the app :-(
 type ActiveView = Arc<relm4::SharedState<ViewName>>;
 
+/// Can the pictures library directory be read?
+/// A library on a network share is offline when the share isn't mounted, in which case
+/// views show cached thumbnails and metadata instead of reading files.
+type LibraryAvailability = Arc<relm4::SharedState<Availability>>;
+
 // Visual items to be shared between various views.
 // State is loaded by the `load_library` background task.
 type SharedState = Arc<relm4::SharedState<Vec<Arc<fotema_core::Visual>>>>;
@@ -162,15 +216,33 @@ pub(super) struct App {
     // Album for individual person.
     person_album: Controller<PersonAlbum>,
 
+    // Album of pictures with captions matching a search.
+    search_album: Controller<SearchAlbum>,
+
     /// Album with photos overlayed onto a map
     places_page: Controller<PlacesAlbum>,
 
+    /// Timeline of trips and events
+    events_page: Controller<EventsAlbum>,
+
     // Grid of folders of photos
     folders_album: Controller<FoldersAlbum>,
 
     // Folder album currently being viewed
     folder_album: Controller<Album>,
 
+    // Filter for folder or place currently shown in folder_album.
+    folder_album_filter: AlbumFilter,
+
+    // Slideshow movie of an album
+    memory_movie: AsyncController<MemoryMovie>,
+
+    // Preview and import of files dropped on the window
+    import_dialog: AsyncController<ImportDialog>,
+
+    // Files that failed processing
+    problem_files: Controller<ProblemFiles>,
+
     // Main navigation. Parent of library stack.
     main_navigation: adw::OverlaySplitView,
 
@@ -191,7 +263,25 @@ pub(super) struct App {
     // Message banner
     banner: adw::Banner,
 
+    // Shown in header bar when library is offline.
+    offline_badge: gtk::Box,
+
+    library_availability: LibraryAvailability,
+
+    // Kept so that mount signals keep arriving.
+    _volume_monitor: gio::VolumeMonitor,
+
+    // Rescans the library when it changes while running in the background.
+    library_watcher: LibraryWatcher,
+
     settings_state: SettingsState,
+
+    // Library contents. Used to count items for the indexing complete notification.
+    library_state: SharedState,
+
+    // When a background task started processing the library since bootstrap last completed,
+    // and whether the library was empty at the time.
+    indexing_started: Option<(Instant, bool)>,
 }
 
 #[derive(Debug)]
@@ -200,6 +290,23 @@ pub(super) enum AppMsg {
 
     Quit,
 
+    /// Main window close button clicked
+    CloseWindow,
+
+    /// Library directory contents have changed
+    Rescan,
+
+    /// A file system has been mounted, or it is time to check again,
+    /// so see if an offline library has come back.
+    RetryOfflineLibrary,
+
+    /// Library directory has been checked to see if it can be read.
+    LibraryChecked(Availability),
+
+    /// Documents portal path for the library directory has been looked up again.
+    /// None if it couldn't be found or if not running in a sandbox.
+    LibraryDirRestored(Option<PathBuf>),
+
     /// Ignore event
     Ignore,
 
@@ -219,8 +326,13 @@ pub(super) enum AppMsg {
 
     ViewGeographicArea(CellIndex),
 
+    ViewEvent(event::Event),
+
     ViewPerson(people::Person),
 
+    // Show page for searching titles and captions.
+    ViewSearch,
+
     PersonDeleted,
 
     PersonRenamed,
@@ -236,6 +348,24 @@ pub(super) enum AppMsg {
     ScanPictureForFaces(PictureId),
     ScanPicturesForFaces,
 
+    // Files have been released from quarantine and should be processed again
+    RetryProblemFiles,
+
+    // Create a memory movie of the album being viewed
+    CreateAlbumMemoryMovie,
+
+    // Create a memory movie of pictures taken on this day in previous years
+    CreateOnThisDayMemoryMovie,
+
+    // A memory movie has been saved to the pictures library
+    MemoryMovieSaved,
+
+    // Files or folders have been dropped on the window to import
+    Import(Vec<PathBuf>),
+
+    // Files have been imported into the pictures library
+    Imported,
+
     // Stop all background tasks
     StopBackgroundTasks,
 
@@ -249,16 +379,19 @@ pub(super) enum AppMsg {
     SettingsChanged(Settings),
 
     /// Onboarding process is complete and user has selected the picture base directory
-    OnboardDone(PathBuf),
+    OnboardDone(portal::GrantedDirectory),
 }
 
 relm4::new_action_group!(pub(super) WindowActionGroup, "win");
 relm4::new_stateless_action!(PreferencesAction, WindowActionGroup, "preferences");
 relm4::new_stateless_action!(AboutAction, WindowActionGroup, "about");
+relm4::new_stateless_action!(MemoryMovieAlbumAction, WindowActionGroup, "memory-movie-album");
+relm4::new_stateless_action!(MemoryMovieOnThisDayAction, WindowActionGroup, "memory-movie-on-this-day");
 
 #[relm4::component(pub)]
 impl SimpleComponent for App {
-    type Init = ();
+    /// Start with window hidden, running in the background.
+    type Init = bool;
     type Input = AppMsg;
     type Output = ();
     type Widgets = AppWidgets;
@@ -269,6 +402,12 @@ impl SimpleComponent for App {
                 &fl!("primary-menu-preferences") => PreferencesAction,
                 &fl!("primary-menu-about") => AboutAction,
             }
+        },
+        memory_movie_menu: {
+            section! {
+                &fl!("memory-movie-menu", "album") => MemoryMovieAlbumAction,
+                &fl!("memory-movie-menu", "on-this-day") => MemoryMovieOnThisDayAction,
+            }
         }
     }
 
@@ -283,7 +422,7 @@ impl SimpleComponent for App {
             set_height_request: 294,
 
             connect_close_request[sender] => move |_| {
-                sender.input(AppMsg::Quit);
+                sender.input(AppMsg::CloseWindow);
                 glib::Propagation::Stop
             },
 
@@ -362,6 +501,21 @@ impl SimpleComponent for App {
 
                                     #[local_ref]
                                     pack_end = &spinner -> adw::Spinner,
+
+                                    pack_end = &gtk::Button {
+                                        set_icon_name: "system-search-symbolic",
+                                        set_tooltip_text: Some(&fl!("search-album", "tooltip")),
+                                        connect_clicked => AppMsg::ViewSearch,
+                                    },
+
+                                    #[local_ref]
+                                    pack_end = &offline_badge -> gtk::Box,
+
+                                    pack_end = &gtk::MenuButton {
+                                        set_icon_name: "video-reel-symbolic",
+                                        set_tooltip_text: Some(&fl!("memory-movie-menu", "tooltip")),
+                                        set_menu_model: Some(&memory_movie_menu),
+                                    },
                                 },
 
                                 // NOTE I would like this to be an adw::ViewStack
@@ -433,6 +587,14 @@ impl SimpleComponent for App {
                                             set_name: ViewName::Places.into(),
                                         },
 
+                                        add_child = &gtk::Box {
+                                            set_orientation: gtk::Orientation::Vertical,
+                                            container_add: model.events_page.widget(),
+                                        } -> {
+                                            set_title: &fl!("events-page"),
+                                            set_name: ViewName::Events.into(),
+                                        },
+
                                         add_child = &gtk::Box {
                                             set_orientation: gtk::Orientation::Vertical,
                                             container_add: model.selfies_page.widget(),
@@ -458,6 +620,16 @@ impl SimpleComponent for App {
                                             // NOTE gtk::StackSidebar doesn't show icon :-/
                                             set_icon_name: "folder-symbolic",
                                         },
+
+                                        add_child = &gtk::Box {
+                                            set_orientation: gtk::Orientation::Vertical,
+                                            container_add: model.problem_files.widget(),
+                                        } -> {
+                                            set_title: &fl!("problem-files-page"),
+                                            set_name: ViewName::ProblemFiles.into(),
+                                            // NOTE gtk::StackSidebar doesn't show icon :-/
+                                            set_icon_name: "dialog-warning-symbolic",
+                                        },
                                     },
                                 },
                             },
@@ -473,7 +645,13 @@ impl SimpleComponent for App {
                             set_title_widget = &gtk::Label {
                                 set_label: &fl!("folder-album"),
                                 add_css_class: "title",
-                            }
+                            },
+
+                            pack_end = &gtk::Button {
+                                set_icon_name: "video-reel-symbolic",
+                                set_tooltip_text: Some(&fl!("memory-movie-menu", "tooltip")),
+                                connect_clicked => AppMsg::CreateAlbumMemoryMovie,
+                            },
                         },
 
                         #[wrap(Some)]
@@ -486,6 +664,12 @@ impl SimpleComponent for App {
                     model.person_album.widget(),
                 },
 
+                adw::NavigationPage {
+                    set_tag: Some("search_album"),
+                    set_title: &fl!("search-album"),
+                    model.search_album.widget(),
+                },
+
                 // Page for showing a single photo.
                 adw::NavigationPage {
                     set_tag: Some("picture"),
@@ -514,7 +698,7 @@ impl SimpleComponent for App {
     }
 
     fn init(
-        _init: Self::Init,
+        start_in_background: Self::Init,
         root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
@@ -530,9 +714,11 @@ impl SimpleComponent for App {
         let con = Arc::new(Mutex::new(con));
 
         let people_repo = people::Repository::open(&data_dir, con.clone()).unwrap();
+        let caption_repo = caption::Repository::open(con.clone()).unwrap();
 
         let state = SharedState::new(relm4::SharedState::new());
         let active_view = ActiveView::new(relm4::SharedState::new());
+        let library_availability = LibraryAvailability::new(relm4::SharedState::new());
         let adaptive_layout = Arc::new(adaptive::LayoutState::new());
 
         let settings_state = SettingsState::new(relm4::SharedState::new());
@@ -566,13 +752,16 @@ impl SimpleComponent for App {
                 BootstrapOutput::TaskStarted(msg) => AppMsg::TaskStarted(msg),
                 BootstrapOutput::Completed => AppMsg::BootstrapCompleted,
                 BootstrapOutput::Stopping => AppMsg::StoppingBackgroundTasks,
+                BootstrapOutput::LibraryChecked(availability) => {
+                    AppMsg::LibraryChecked(availability)
+                }
             });
 
         let onboard =
             Onboard::builder()
                 .launch(())
                 .forward(sender.input_sender(), |msg| match msg {
-                    OnboardOutput::Done(pic_base_dir) => AppMsg::OnboardDone(pic_base_dir),
+                    OnboardOutput::Done(dir) => AppMsg::OnboardDone(dir),
                 });
 
         let onboard_view = adw::ToolbarView::new();
@@ -593,6 +782,8 @@ impl SimpleComponent for App {
                 bootstrap_progress_monitor,
                 adaptive_layout.clone(),
                 people_repo.clone(),
+                caption_repo.clone(),
+                library_availability.clone(),
             ))
             .forward(sender.input_sender(), |msg| match msg {
                 ViewNavOutput::TranscodeAll => AppMsg::TranscodeAll,
@@ -690,6 +881,20 @@ impl SimpleComponent for App {
             PersonAlbumInput::Sort(settings.album_sort)
         });
 
+        let search_album = SearchAlbum::builder()
+            .launch((state.clone(), caption_repo, active_view.clone()))
+            .forward(sender.input_sender(), |msg| match msg {
+                SearchAlbumOutput::Selected(id, filter) => AppMsg::View(id, filter),
+            });
+
+        state.subscribe(search_album.sender(), |_| SearchAlbumInput::Refresh);
+        adaptive_layout.subscribe(search_album.sender(), |layout| {
+            SearchAlbumInput::Adapt(*layout)
+        });
+        settings_state.subscribe(search_album.sender(), |settings| {
+            SearchAlbumInput::Sort(settings.album_sort)
+        });
+
         let places_page = PlacesAlbum::builder()
             .launch((state.clone(), active_view.clone()))
             .forward(sender.input_sender(), |msg| match msg {
@@ -706,6 +911,18 @@ impl SimpleComponent for App {
             PlacesAlbumInput::Adapt(*layout)
         });
 
+        let events_page = EventsAlbum::builder()
+            .launch((
+                state.clone(),
+                active_view.clone(),
+                event::Repository::open(con.clone()).unwrap(),
+            ))
+            .forward(sender.input_sender(), |msg| match msg {
+                EventsAlbumOutput::Selected(event) => AppMsg::ViewEvent(event),
+            });
+
+        state.subscribe(events_page.sender(), |_| EventsAlbumInput::Refresh);
+
         let folders_album = FoldersAlbum::builder()
             .launch((state.clone(), active_view.clone()))
             .forward(sender.input_sender(), |msg| match msg {
@@ -735,6 +952,29 @@ impl SimpleComponent for App {
             AlbumInput::Sort(settings.album_sort)
         });
 
+        let problem_files = ProblemFiles::builder()
+            .launch((con.clone(), settings_state.clone(), active_view.clone()))
+            .forward(sender.input_sender(), |msg| match msg {
+                ProblemFilesOutput::Retried => AppMsg::RetryProblemFiles,
+            });
+
+        let memory_movie = MemoryMovie::builder()
+            .launch((
+                state.clone(),
+                settings_state.clone(),
+                library_availability.clone(),
+                root.clone(),
+            ))
+            .forward(sender.input_sender(), |msg| match msg {
+                MemoryMovieOutput::Saved => AppMsg::MemoryMovieSaved,
+            });
+
+        let import_dialog = ImportDialog::builder()
+            .launch((settings_state.clone(), library_availability.clone(), root.clone()))
+            .forward(sender.input_sender(), |msg| match msg {
+                ImportOutput::Imported => AppMsg::Imported,
+            });
+
         let about_dialog = AboutDialog::builder().launch(root.clone()).detach();
 
         let preferences_dialog = PreferencesDialog::builder()
@@ -756,6 +996,27 @@ impl SimpleComponent for App {
             .tooltip_text(fl!("banner-button-stop", "tooltip"))
             .build();
 
+        let offline_badge = gtk::Box::builder()
+            .spacing(6)
+            .valign(gtk::Align::Center)
+            .visible(false)
+            .tooltip_text(fl!("library-offline-badge", "tooltip"))
+            .css_classes(["library-offline-badge"])
+            .build();
+        offline_badge.append(&gtk::Image::from_icon_name("network-offline-symbolic"));
+        offline_badge.append(&gtk::Label::new(Some(&fl!("library-offline-badge"))));
+
+        let volume_monitor = gio::VolumeMonitor::get();
+        {
+            let sender = sender.clone();
+            volume_monitor.connect_mount_added(move |_, _| sender.input(AppMsg::RetryOfflineLibrary));
+        }
+
+        let library_watcher = {
+            let sender = sender.clone();
+            LibraryWatcher::new(move || sender.input(AppMsg::Rescan))
+        };
+
         let model = Self {
             adaptive_layout,
             bootstrap,
@@ -773,11 +1034,17 @@ impl SimpleComponent for App {
             videos_page,
             people_page,
             person_album,
+            search_album,
             places_page,
+            events_page,
             selfies_page,
             show_selfies,
             folders_album,
             folder_album,
+            folder_album_filter: AlbumFilter::None,
+            memory_movie,
+            import_dialog,
+            problem_files,
 
             main_navigation: main_navigation.clone(),
             main_stack: main_stack.clone(),
@@ -790,7 +1057,15 @@ impl SimpleComponent for App {
 
             banner: banner.clone(),
 
+            offline_badge: offline_badge.clone(),
+            library_availability: library_availability.clone(),
+            _volume_monitor: volume_monitor,
+            library_watcher,
+
             settings_state: settings_state.clone(),
+
+            library_state: state.clone(),
+            indexing_started: None,
         };
 
         let widgets = view_output!();
@@ -811,28 +1086,91 @@ impl SimpleComponent for App {
             })
         };
 
+        let memory_movie_album_action = {
+            let sender = sender.clone();
+            RelmAction::<MemoryMovieAlbumAction>::new_stateless(move |_| {
+                sender.input(AppMsg::CreateAlbumMemoryMovie);
+            })
+        };
+
+        let memory_movie_on_this_day_action = {
+            let sender = sender.clone();
+            RelmAction::<MemoryMovieOnThisDayAction>::new_stateless(move |_| {
+                sender.input(AppMsg::CreateOnThisDayMemoryMovie);
+            })
+        };
+
         actions.add_action(about_action);
         actions.add_action(preferences_action);
+        actions.add_action(memory_movie_album_action);
+        actions.add_action(memory_movie_on_this_day_action);
 
         actions.register_for_widget(&widgets.main_window);
 
+        // Files and folders dragged from a file manager or a camera's memory card.
+        let drop_target = gtk::DropTarget::new(gdk::FileList::static_type(), gdk::DragAction::COPY);
+        {
+            let sender = sender.clone();
+            drop_target.connect_drop(move |_, value, _, _| {
+                let Ok(files) = value.get::<gdk::FileList>() else {
+                    return false;
+                };
+                let paths: Vec<PathBuf> = files.files().iter().filter_map(|f| f.path()).collect();
+                if paths.is_empty() {
+                    return false;
+                }
+                sender.input(AppMsg::Import(paths));
+                true
+            });
+        }
+        widgets.main_window.add_controller(drop_target);
+
         widgets.load_window_size();
 
         // Get startup window size and propagate so all components have correct narrow/wide layout.
         sender.input(AppMsg::Activate(widgets.main_window.default_width()));
 
-        let settings = settings_state.read();
-        let is_onboarding_complete =
-            settings.is_onboarding_complete && settings.pictures_base_dir.exists();
-        if is_onboarding_complete {
+        // The Documents portal path for the pictures directory might have changed
+        // since the last launch, so look it up again from the persisted document ID.
+        // Talking to the portal can be slow, so don't hold up showing the window.
+        let restore_doc_id = {
+            let settings = settings_state.read();
+            settings
+                .pictures_base_dir_doc_id
+                .clone()
+                .filter(|_| {
+                    settings.is_onboarding_complete && !settings.pictures_base_dir.exists()
+                })
+        };
+
+        let is_onboarding_complete = if let Some(doc_id) = restore_doc_id {
+            let dir = settings_state.read().pictures_base_dir.clone();
+            let sender = sender.clone();
+            relm4::spawn_local(async move {
+                let restored_dir = portal::restore(&dir, &doc_id).await;
+                sender.input(AppMsg::LibraryDirRestored(restored_dir));
+            });
+
+            // Show the cached library while the portal path is looked up.
             model.picture_navigation_view.set_visible(true);
             model.onboard_view.set_visible(false);
-            model.bootstrap.emit(BootstrapInput::Configure(
-                settings.pictures_base_dir.clone(),
-            ));
+            true
         } else {
-            model.picture_navigation_view.set_visible(false);
-            model.onboard_view.set_visible(true);
+            model.open_library()
+        };
+
+        let run_in_background = settings_state.read().run_in_background;
+        if start_in_background && run_in_background && is_onboarding_complete {
+            info!("Starting in background");
+            widgets.main_window.set_visible(false);
+        }
+
+        {
+            let sender = sender.clone();
+            glib::timeout_add_seconds_local(OFFLINE_RETRY_INTERVAL_SECONDS, move || {
+                sender.input(AppMsg::RetryOfflineLibrary);
+                glib::ControlFlow::Continue
+            });
         }
 
         ComponentParts { model, widgets }
@@ -848,13 +1186,76 @@ impl SimpleComponent for App {
                 }
             }
             AppMsg::Quit => main_application().quit(),
+            AppMsg::CloseWindow => {
+                if self.settings_state.read().run_in_background {
+                    info!("Hiding window and continuing in background");
+                    if let Some(window) = main_application().active_window() {
+                        window.set_visible(false);
+                    }
+                } else {
+                    main_application().quit();
+                }
+            }
+            AppMsg::Rescan => {
+                if self.settings_state.read().run_in_background {
+                    self.bootstrap.emit(BootstrapInput::Rescan);
+                }
+            }
+            AppMsg::RetryOfflineLibrary => {
+                // Rescanning checks the library first and stops again if it is still offline.
+                // If it is back, then the rescan picks up changes made while it was away.
+                if *self.library_availability.read() == Availability::Offline {
+                    info!("Checking if offline library has come back");
+                    self.bootstrap.emit(BootstrapInput::Rescan);
+                }
+            }
+            AppMsg::LibraryChecked(availability) => {
+                if *self.library_availability.read() != availability {
+                    info!("Library is now {:?}", availability);
+                    *self.library_availability.write() = availability;
+
+                    // Directories of an offline library can't be watched,
+                    // so find them again now that they can be read.
+                    self.library_watcher.unwatch();
+                    self.update_library_watcher();
+                }
+                self.offline_badge.set_visible(availability == Availability::Offline);
+            }
+            AppMsg::LibraryDirRestored(dir) => {
+                if let Some(dir) = dir {
+                    self.settings_state.write().pictures_base_dir = dir;
+                }
+                self.open_library();
+            }
             AppMsg::Ignore => {
                 // info!("Intentionally ignoring a message");
             }
+            AppMsg::CreateAlbumMemoryMovie => {
+                let filter = self.current_album_filter();
+                self.memory_movie
+                    .emit(MemoryMovieInput::Create(MemoryMovieSource::Album(filter)));
+            }
+            AppMsg::CreateOnThisDayMemoryMovie => {
+                self.memory_movie
+                    .emit(MemoryMovieInput::Create(MemoryMovieSource::OnThisDay));
+            }
+            AppMsg::MemoryMovieSaved => {
+                self.bootstrap.emit(BootstrapInput::Rescan);
+            }
+            AppMsg::Import(paths) => {
+                // No library to import into until onboarding is complete.
+                if self.picture_navigation_view.is_visible() {
+                    self.import_dialog.emit(ImportInput::Preview(paths));
+                }
+            }
+            AppMsg::Imported => {
+                self.bootstrap.emit(BootstrapInput::Rescan);
+            }
             AppMsg::SettingsChanged(settings) => {
                 if let Err(e) = App::save_settings(&settings) {
                     error!("Failed to save settings: {}", e);
                 }
+                self.update_library_watcher();
             }
             AppMsg::ToggleSidebar => {
                 let show = self.main_navigation.shows_sidebar();
@@ -907,7 +1308,10 @@ impl SimpleComponent for App {
                     ViewName::Folder => self.folder_album.emit(AlbumInput::Activate),
                     ViewName::People => self.people_page.emit(PeopleAlbumInput::Activate),
                     ViewName::Person => self.person_album.emit(PersonAlbumInput::Activate),
+                    ViewName::Search => self.search_album.emit(SearchAlbumInput::Activate),
                     ViewName::Places => self.places_page.emit(PlacesAlbumInput::Activate),
+                    ViewName::Events => self.events_page.emit(EventsAlbumInput::Activate),
+                    ViewName::ProblemFiles => self.problem_files.emit(ProblemFilesInput::Activate),
                     ViewName::Nothing => warn!("Nothing activated... which should not happen"),
                 }
             }
@@ -922,15 +1326,24 @@ impl SimpleComponent for App {
                 self.view_nav.emit(ViewNavInput::Hidden);
             }
             AppMsg::ViewFolder(path) => {
+                self.folder_album_filter = AlbumFilter::Folder(path);
                 self.folder_album.emit(AlbumInput::Activate);
                 self.folder_album
-                    .emit(AlbumInput::Filter(AlbumFilter::Folder(path)));
+                    .emit(AlbumInput::Filter(self.folder_album_filter.clone()));
+                self.picture_navigation_view.push_by_tag("album");
+            }
+            AppMsg::ViewEvent(event) => {
+                self.folder_album_filter = AlbumFilter::Event(event);
+                self.folder_album.emit(AlbumInput::Activate);
+                self.folder_album
+                    .emit(AlbumInput::Filter(self.folder_album_filter.clone()));
                 self.picture_navigation_view.push_by_tag("album");
             }
             AppMsg::ViewGeographicArea(cell_index) => {
+                self.folder_album_filter = AlbumFilter::GeographicArea(cell_index);
                 self.folder_album.emit(AlbumInput::Activate);
                 self.folder_album
-                    .emit(AlbumInput::Filter(AlbumFilter::GeographicArea(cell_index)));
+                    .emit(AlbumInput::Filter(self.folder_album_filter.clone()));
                 self.picture_navigation_view.push_by_tag("album");
             }
             AppMsg::ViewPerson(person) => {
@@ -940,6 +1353,11 @@ impl SimpleComponent for App {
                 self.person_album.emit(PersonAlbumInput::View(person));
                 self.picture_navigation_view.push_by_tag("person_album");
             }
+            AppMsg::ViewSearch => {
+                info!("Viewing search");
+                self.search_album.emit(SearchAlbumInput::Activate);
+                self.picture_navigation_view.push_by_tag("search_album");
+            }
             AppMsg::PersonDeleted => {
                 self.picture_navigation_view.pop();
                 self.people_page.emit(PeopleAlbumInput::Refresh);
@@ -954,8 +1372,18 @@ impl SimpleComponent for App {
                 self.banner
                     .set_button_label(Some(&fl!("banner-button-stop", "label")));
 
+                if self.indexing_started.is_none()
+                    && !matches!(
+                        task_name,
+                        TaskName::LoadLibrary | TaskName::CheckLibrary | TaskName::Scan(_)
+                    )
+                {
+                    let is_first_index = self.library_state.read().is_empty();
+                    self.indexing_started = Some((Instant::now(), is_first_index));
+                }
+
                 match task_name {
-                    TaskName::LoadLibrary => {
+                    TaskName::LoadLibrary | TaskName::CheckLibrary => {
                         // do nothing
                     }
                     TaskName::Scan(MediaType::Photo) => {
@@ -1000,6 +1428,15 @@ impl SimpleComponent for App {
                 info!("Bootstrap completed.");
                 self.spinner.set_visible(false);
                 self.banner.set_revealed(false);
+                self.problem_files.emit(ProblemFilesInput::Refresh);
+
+                // Only notify after the first full index of a library, or after a long
+                // index such as a big import, not on every rescan.
+                if let Some((started, is_first_index)) = self.indexing_started.take() {
+                    if is_first_index || started.elapsed() >= LONG_INDEXING_DURATION {
+                        self.notify_library_ready();
+                    }
+                }
             }
             AppMsg::TranscodeAll => {
                 info!("Transcode all");
@@ -1014,6 +1451,10 @@ impl SimpleComponent for App {
                 info!("Scan pictures for faces");
                 self.bootstrap.emit(BootstrapInput::ScanPicturesForFaces);
             }
+            AppMsg::RetryProblemFiles => {
+                info!("Retry problem files");
+                self.bootstrap.emit(BootstrapInput::RetryQuarantined);
+            }
             AppMsg::StopBackgroundTasks => {
                 info!("Stop all background tasks");
                 self.banner.set_button_label(None);
@@ -1040,13 +1481,15 @@ impl SimpleComponent for App {
                 // Notify of a change of layout.
                 *self.adaptive_layout.write() = adaptive::Layout::Wide;
             }
-            AppMsg::OnboardDone(pic_base_dir) => {
+            AppMsg::OnboardDone(dir) => {
                 let mut settings = self.settings_state.read().clone();
                 settings.is_onboarding_complete = true;
-                settings.pictures_base_dir = pic_base_dir.clone();
+                settings.pictures_base_dir = dir.path.clone();
+                settings.pictures_base_dir_doc_id = dir.doc_id;
+                settings.pictures_base_dir_host = dir.host_path;
                 *self.settings_state.write() = settings;
 
-                self.bootstrap.emit(BootstrapInput::Configure(pic_base_dir));
+                self.bootstrap.emit(BootstrapInput::Configure(dir.path));
                 self.picture_navigation_view.set_visible(true);
                 self.onboard_view.set_visible(false);
             }
@@ -1059,6 +1502,93 @@ impl SimpleComponent for App {
 }
 
 impl App {
+    /// Start loading the library if onboarding is complete and the library directory
+    /// can be found, otherwise show onboarding. Returns true if onboarding is complete.
+    fn open_library(&self) -> bool {
+        let settings = self.settings_state.read();
+
+        // A library on a network share that isn't mounted is still shown from the cache.
+        let is_network_library = network_share::is_network_path(&settings.pictures_base_dir)
+            || settings
+                .pictures_base_dir_host
+                .as_ref()
+                .is_some_and(|dir| network_share::is_network_path(dir));
+
+        let is_onboarding_complete = settings.is_onboarding_complete
+            && (settings.pictures_base_dir.exists() || is_network_library);
+        if is_onboarding_complete {
+            self.picture_navigation_view.set_visible(true);
+            self.onboard_view.set_visible(false);
+            self.bootstrap.emit(BootstrapInput::Configure(
+                settings.pictures_base_dir.clone(),
+            ));
+        } else {
+            self.picture_navigation_view.set_visible(false);
+            self.onboard_view.set_visible(true);
+        }
+
+        drop(settings);
+        self.update_library_watcher();
+
+        is_onboarding_complete
+    }
+
+    /// Watch the library for changes only while running in the background,
+    /// so that it is up to date when the window is opened.
+    fn update_library_watcher(&self) {
+        let settings = self.settings_state.read();
+        if settings.is_onboarding_complete && settings.run_in_background {
+            self.library_watcher.watch(&settings.pictures_base_dir);
+        } else {
+            self.library_watcher.unwatch();
+        }
+    }
+
+    /// Filter for album currently being viewed. Views that aren't albums of photos,
+    /// such as the people or places overviews, fall back to the whole library.
+    fn current_album_filter(&self) -> AlbumFilter {
+        let is_folder_album = self
+            .picture_navigation_view
+            .visible_page()
+            .and_then(|page| page.tag())
+            .is_some_and(|tag| tag == "album");
+
+        if is_folder_album {
+            return self.folder_album_filter.clone();
+        }
+
+        let view_name = self
+            .main_stack
+            .visible_child_name()
+            .and_then(|x| ViewName::from_str(x.as_str()).ok())
+            .unwrap_or(ViewName::Nothing);
+
+        match view_name {
+            ViewName::Selfies => AlbumFilter::Selfies,
+            ViewName::Animated => AlbumFilter::Motion,
+            ViewName::Videos => AlbumFilter::Videos,
+            _ => AlbumFilter::All,
+        }
+    }
+
+    /// Let the user know the library is ready if they aren't looking at Fotema.
+    fn notify_library_ready(&self) {
+        let app = main_application();
+        let is_window_active = app.active_window().is_some_and(|w| w.is_active());
+        if is_window_active {
+            return;
+        }
+
+        let count = self.library_state.read().len();
+        info!("Sending library ready notification for {} items", count);
+
+        let notification = gio::Notification::new(&fl!("notification-library-ready", "title"));
+        notification.set_body(Some(&fl!("notification-library-ready-body", count = count)));
+        notification.set_default_action("app.show-window");
+        notification.add_button(&fl!("notification-library-ready", "open"), "app.show-window");
+        app.send_notification(Some("library-ready"), &notification);
+    }
+
     pub fn load_settings() -> Result<Settings> {
         info!("Loading settings");
         let gio_settings = gio::Settings::new(APP_ID);
@@ -1074,6 +1604,17 @@ impl App {
             pictures_base_dir: path_encoding::from_base64(
                 &gio_settings.string("pictures-base-dir-b64").into(),
             )?,
+            pictures_base_dir_doc_id: Some(gio_settings.string("pictures-base-dir-doc-id"))
+                .filter(|doc_id| !doc_id.is_empty())
+                .map(|doc_id| doc_id.to_string()),
+            pictures_base_dir_host: Some(gio_settings.string("pictures-base-dir-host-b64"))
+                .filter(|host| !host.is_empty())
+                .and_then(|host| path_encoding::from_base64(&host.into()).ok()),
+            run_in_background: gio_settings.boolean("run-in-background"),
+            organize_imports: gio_settings.boolean("organize-imports"),
+            import_template: gio_settings.string("import-template").to_string(),
+            import_transfer: import::Transfer::from_str(&gio_settings.string("import-transfer"))
+                .unwrap_or_default(),
         })
     }
 
@@ -1088,6 +1629,22 @@ impl App {
             "pictures-base-dir-b64",
             &path_encoding::to_base64(settings.pictures_base_dir.as_ref()),
         )?;
+        gio_settings.set_string(
+            "pictures-base-dir-doc-id",
+            settings.pictures_base_dir_doc_id.as_deref().unwrap_or_default(),
+        )?;
+        gio_settings.set_string(
+            "pictures-base-dir-host-b64",
+            &settings
+                .pictures_base_dir_host
+                .as_ref()
+                .map(|host| path_encoding::to_base64(host))
+                .unwrap_or_default(),
+        )?;
+        gio_settings.set_boolean("run-in-background", settings.run_in_background)?;
+        gio_settings.set_boolean("organize-imports", settings.organize_imports)?;
+        gio_settings.set_string("import-template", &settings.import_template)?;
+        gio_settings.set_string("import-transfer", settings.import_transfer.as_ref())?;
         Ok(())
     }
 }
@@ -8,6 +8,7 @@ use relm4::{
 use crate::app::Settings;
 use crate::config::APP_ID;
 use fotema_core::database;
+use fotema_core::network_share::{self, Availability};
 use fotema_core::people;
 use fotema_core::photo;
 use fotema_core::video;
@@ -62,6 +63,7 @@ pub enum MediaType {
 #[derive(Debug)]
 pub enum TaskName {
     LoadLibrary,
+    CheckLibrary,
     Scan(MediaType),
     Enrich(MediaType),
     MotionPhoto,
@@ -96,6 +98,15 @@ pub enum BootstrapInput {
     // Queue task for transcoding videos
     TranscodeAll,
 
+    /// Queue tasks for reprocessing files released from quarantine.
+    RetryQuarantined,
+
+    /// Queue tasks to rescan the library for new, changed, and deleted files.
+    Rescan,
+
+    /// Library directory has been checked to see if it can be read.
+    LibraryChecked(Availability),
+
     /// A background task has started.
     TaskStarted(TaskName),
 
@@ -114,6 +125,9 @@ pub enum BootstrapOutput {
 
     // Tasks are in the process of stopping
     Stopping,
+
+    // Library directory has been checked to see if it can be read.
+    LibraryChecked(Availability),
 }
 
 type Task = dyn Fn() + Send + Sync;
@@ -127,7 +141,10 @@ pub struct Controllers {
 
     settings_state: SettingsState,
 
-    //pic_base_dir: Option<PathBuf>,
+    pictures_base_dir: PathBuf,
+
+    /// Is the library directory unreadable, such as a network share that isn't mounted?
+    is_offline: bool,
 
     // Stop background tasks.
     stop: Arc<AtomicBool>,
@@ -162,6 +179,10 @@ pub struct Controllers {
 
     // Is a task currently running?
     is_running: bool,
+
+    // Was a rescan requested while tasks were running? Tasks that have already
+    // run might have missed the changes, so rescan once they complete.
+    is_rescan_pending: bool,
 }
 
 impl Controllers {
@@ -183,6 +204,14 @@ impl Controllers {
                     }
                 }
             }
+            BootstrapInput::ScanPictureForFaces(_)
+            | BootstrapInput::ScanPicturesForFaces
+            | BootstrapInput::TranscodeAll
+            | BootstrapInput::RetryQuarantined
+                if self.is_offline =>
+            {
+                info!("Ignoring {:?} because library is offline", msg);
+            }
             BootstrapInput::ScanPictureForFaces(picture_id) => {
                 info!("Queueing task to scan picture {} for faces", picture_id);
                 self.add_task_photo_detect_faces_for_one(picture_id);
@@ -200,6 +229,44 @@ impl Controllers {
                 self.add_task_video_transcode();
                 self.run_if_idle();
             }
+            BootstrapInput::RetryQuarantined => {
+                info!("Queueing tasks to reprocess files released from quarantine");
+                self.add_task_photo_enrich();
+                self.add_task_video_enrich();
+                self.add_task_photo_thumbnail();
+                self.add_task_video_thumbnail();
+                self.add_task_photo_extract_motion();
+                self.add_task_load_library(sender.input_sender().clone());
+                self.run_if_idle();
+            }
+            BootstrapInput::Rescan => {
+                if self.is_running {
+                    info!("Deferring rescan until running tasks complete");
+                    self.is_rescan_pending = true;
+                    return;
+                }
+                info!("Queueing tasks to rescan library");
+                self.add_library_tasks(sender.input_sender().clone());
+                self.run_if_idle();
+            }
+            BootstrapInput::LibraryChecked(availability) => {
+                self.is_offline = availability == Availability::Offline;
+
+                if self.is_offline {
+                    // Every file would look deleted, so don't scan or clean the library.
+                    // Pictures will be reconciled by the next rescan after the library returns.
+                    warn!(
+                        "Library at {:?} is offline. Skipping remaining tasks.",
+                        self.pictures_base_dir
+                    );
+                    if let Ok(mut tasks) = self.pending_tasks.lock() {
+                        tasks.clear();
+                    }
+                }
+
+                let _ = sender.output(BootstrapOutput::LibraryChecked(availability));
+                sender.input(BootstrapInput::TaskCompleted(TaskName::CheckLibrary, None));
+            }
             BootstrapInput::TaskStarted(task_name) => {
                 info!("Task started: {:?}", task_name);
                 let _ = sender.output(BootstrapOutput::TaskStarted(task_name));
@@ -226,7 +293,11 @@ impl Controllers {
                 if !self.is_running {
                     // Note: AtomicBool::swap returns previous value.
                     if self.stop.swap(false, Ordering::Relaxed) {
+                        self.is_rescan_pending = false;
                         sender.input(BootstrapInput::Stopped);
+                    } else if self.is_rescan_pending {
+                        self.is_rescan_pending = false;
+                        sender.input(BootstrapInput::Rescan);
                     }
                 }
             }
@@ -248,6 +319,45 @@ impl Controllers {
         };
     }
 
+    /// Queue all tasks for bringing the library up to date.
+    fn add_library_tasks(&mut self, bootstrap_sender: Sender<BootstrapInput>) {
+        // Tasks will execute in the order added.
+
+        // Initial library load to reduce time from starting app and seeing a photo grid
+        self.add_task_load_library(bootstrap_sender.clone());
+
+        // Library might be on a network share that isn't mounted. Check after the initial
+        // load so that an empty library directory can be told apart from an empty library.
+        self.add_task_check_library(bootstrap_sender.clone());
+
+        self.add_task_photo_scan();
+        self.add_task_video_scan();
+        self.add_task_photo_enrich();
+        self.add_task_video_enrich();
+
+        // If loaded library is currently empty, then refresh now that the photo and video scans
+        // are complete. Note: should do this after enriching because otherwise Fotema won't
+        // have processed the orientation metadata and will display pictures incorrectly.
+        self.add_task_load_library(bootstrap_sender.clone());
+
+        self.add_task_photo_thumbnail();
+        self.add_task_video_thumbnail();
+
+        // Check again because a network share could have gone away since the scan
+        // and cleaning would then remove every picture from the library.
+        self.add_task_check_library(bootstrap_sender.clone());
+
+        self.add_task_photo_clean();
+        self.add_task_video_clean();
+        self.add_task_photo_extract_motion();
+        self.add_task_photo_detect_faces();
+        self.add_task_photo_recognize_faces();
+
+        // This is the last background task to complete. Refresh library if there
+        // has been a visible change to the library state.
+        self.add_task_load_library(bootstrap_sender);
+    }
+
     fn add_task_photo_scan(&mut self) {
         let sender = self.photo_scan.sender().clone();
         self.enqueue(Box::new(move || sender.emit(PhotoScanInput::Start)));
@@ -353,6 +463,16 @@ impl Controllers {
         }));
     }
 
+    fn add_task_check_library(&mut self, bootstrap_sender: Sender<BootstrapInput>) {
+        let pictures_base_dir = self.pictures_base_dir.clone();
+        let library_state = self.shared_state.clone();
+        self.enqueue(Box::new(move || {
+            let has_items = !library_state.read().is_empty();
+            let availability = network_share::availability(&pictures_base_dir, has_items);
+            bootstrap_sender.emit(BootstrapInput::LibraryChecked(availability));
+        }));
+    }
+
     fn enqueue(&mut self, task: Box<dyn Fn() + Send + Sync>) {
         if let Ok(mut vec) = self.pending_tasks.lock() {
             vec.push_back(task);
@@ -402,6 +522,10 @@ impl Bootstrap {
         let cache_dir = glib::user_cache_dir().join(APP_ID);
         let _ = std::fs::create_dir_all(&cache_dir);
 
+        if network_share::is_network_path(&pic_base_dir) {
+            info!("Pictures base directory is on a network share");
+        }
+
         let photo_scanner = photo::Scanner::build(&pic_base_dir)?;
 
         let photo_repo =
@@ -607,6 +731,8 @@ impl Bootstrap {
             started_at: None,
             shared_state: self.shared_state.clone(),
             settings_state: self.settings_state.clone(),
+            pictures_base_dir: pic_base_dir,
+            is_offline: false,
             load_library: Arc::new(load_library),
             photo_scan: Arc::new(photo_scan),
             video_scan: Arc::new(video_scan),
@@ -622,34 +748,11 @@ impl Bootstrap {
             video_transcode: Arc::new(video_transcode),
             pending_tasks: Arc::new(Mutex::new(VecDeque::new())),
             is_running: false,
+            is_rescan_pending: false,
             library_stale: Arc::new(AtomicBool::new(true)),
         };
 
-        // Tasks will execute in the order added.
-
-        // Initial library load to reduce time from starting app and seeing a photo grid
-        controllers.add_task_load_library(sender.input_sender().clone());
-        controllers.add_task_photo_scan();
-        controllers.add_task_video_scan();
-        controllers.add_task_photo_enrich();
-        controllers.add_task_video_enrich();
-
-        // If loaded library is currently empty, then refresh now that the photo and video scans
-        // are complete. Note: should do this after enriching because otherwise Fotema won't
-        // have processed the orientation metadata and will display pictures incorrectly.
-        controllers.add_task_load_library(sender.input_sender().clone());
-
-        controllers.add_task_photo_thumbnail();
-        controllers.add_task_video_thumbnail();
-        controllers.add_task_photo_clean();
-        controllers.add_task_video_clean();
-        controllers.add_task_photo_extract_motion();
-        controllers.add_task_photo_detect_faces();
-        controllers.add_task_photo_recognize_faces();
-
-        // This is the last background task to complete. Refresh library if there
-        // has been a visible change to the library state.
-        controllers.add_task_load_library(sender.input_sender().clone());
+        controllers.add_library_tasks(sender.input_sender().clone());
 
         Ok(controllers)
     }
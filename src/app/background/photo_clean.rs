@@ -3,6 +3,8 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use anyhow::Result;
+use fotema_core::photo::model::Picture;
+use fotema_core::photo::PictureStore;
 use rayon::prelude::*;
 use relm4::prelude::*;
 use relm4::Worker;
@@ -39,7 +41,9 @@ impl PhotoClean {
         let start = std::time::Instant::now();
 
         // Scrub pics from database if they no longer exist on the file system.
-        let pics: Vec<fotema_core::photo::model::Picture> = self.repo.all()?;
+        // Broken pics aren't shown, but would otherwise be kept forever.
+        let mut pics: Vec<Picture> = self.repo.all()?;
+        pics.extend(self.repo.find_broken()?);
 
         info!("Found {} photos as candidates for cleaning", pics.len());
 
@@ -56,11 +60,29 @@ impl PhotoClean {
             error!("Failed sending cleanup started: {:?}", e);
         }
 
+        PhotoClean::remove_missing(&self.stop, self.repo.clone(), &pics);
+
+        info!(
+            "Cleaned {} photos in {} seconds.",
+            count,
+            start.elapsed().as_secs()
+        );
+
+        if let Err(e) = sender.output(PhotoCleanOutput::Completed(count)) {
+            error!("Failed sending PhotoCleanOutput::Completed: {:?}", e);
+        }
+
+        Ok(())
+    }
+
+    /// Remove pictures that no longer exist on the file system, along with
+    /// their cache and data files.
+    fn remove_missing(stop: &AtomicBool, repo: impl PictureStore + Clone, pics: &[Picture]) {
         pics.par_iter()
-            .take_any_while(|_| !self.stop.load(Ordering::Relaxed))
+            .take_any_while(|_| !stop.load(Ordering::Relaxed))
             .for_each(|pic| {
                 if !pic.path.exists() {
-                    let mut repo = self.repo.clone();
+                    let mut repo = repo.clone();
                     if let Ok(paths) = repo.find_files_to_cleanup(pic.picture_id) {
                         for path in paths {
                             debug!("Deleting {:?}", path);
@@ -78,18 +100,6 @@ impl PhotoClean {
                     }
                 }
             });
-
-        info!(
-            "Cleaned {} photos in {} seconds.",
-            count,
-            start.elapsed().as_secs()
-        );
-
-        if let Err(e) = sender.output(PhotoCleanOutput::Completed(count)) {
-            error!("Failed sending PhotoCleanOutput::Completed: {:?}", e);
-        }
-
-        Ok(())
     }
 }
 
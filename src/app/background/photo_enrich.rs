@@ -4,10 +4,15 @@
 
 use anyhow::*;
 use fotema_core::photo::metadata;
+use fotema_core::photo::model::Picture;
+use fotema_core::photo::PictureStore;
+use fotema_core::quarantine;
+use rayon::iter::Either;
 use rayon::prelude::*;
 use relm4::prelude::*;
 use relm4::Worker;
 
+use std::result::Result::Ok;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
@@ -38,7 +43,7 @@ pub struct PhotoEnrich {
 impl PhotoEnrich {
     fn enrich(
         stop: Arc<AtomicBool>,
-        mut repo: fotema_core::photo::Repository,
+        mut repo: impl PictureStore,
         sender: &ComponentSender<PhotoEnrich>,
     ) -> Result<()> {
         let start = std::time::Instant::now();
@@ -57,16 +62,7 @@ impl PhotoEnrich {
 
         let _ = sender.output(PhotoEnrichOutput::Started);
 
-        let metadatas = unprocessed
-            .par_iter()
-            .take_any_while(|_| !stop.load(Ordering::Relaxed))
-            .flat_map(|pic| {
-                let result = metadata::from_path(&pic.path);
-                result.map(|m| (pic.picture_id, m))
-            })
-            .collect();
-
-        repo.add_metadatas(metadatas)?;
+        PhotoEnrich::enrich_pictures(&stop, &mut repo, &unprocessed)?;
 
         info!(
             "Extracted {} photo metadatas in {} seconds.",
@@ -80,6 +76,32 @@ impl PhotoEnrich {
 
         Ok(())
     }
+
+    fn enrich_pictures(
+        stop: &AtomicBool,
+        repo: &mut impl PictureStore,
+        pics: &[Picture],
+    ) -> Result<()> {
+        let (metadatas, failures): (Vec<_>, Vec<_>) = pics
+            .par_iter()
+            .take_any_while(|_| !stop.load(Ordering::Relaxed))
+            .map(|pic| (pic.picture_id, metadata::from_path(&pic.path)))
+            .partition_map(|(picture_id, result)| match result {
+                Ok(m) => Either::Left((picture_id, m)),
+                Err(e) => Either::Right((picture_id, e)),
+            });
+
+        repo.add_metadatas(metadatas)?;
+
+        // List pictures we couldn't read as problem files, but keep showing them and
+        // retry them next time, because the failure might only be temporary.
+        for (picture_id, e) in failures {
+            error!("Failed extracting metadata for {}: {:?}", picture_id, e);
+            repo.quarantine(&picture_id, quarantine::Stage::Metadata, &e.to_string())?;
+        }
+
+        Ok(())
+    }
 }
 
 impl Worker for PhotoEnrich {
@@ -108,3 +130,53 @@ impl Worker for PhotoEnrich {
         };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fotema_core::photo::model::ScannedFile;
+    use fotema_core::photo::MemoryRepository;
+    use std::path::{Path, PathBuf};
+
+    fn scanned(path: PathBuf) -> ScannedFile {
+        ScannedFile {
+            path,
+            fs_created_at: None,
+            fs_modified_at: None,
+            fs_file_size_bytes: 0,
+        }
+    }
+
+    #[test]
+    fn test_quarantines_unreadable_pictures() {
+        let library = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let mut repo = MemoryRepository::new(library);
+
+        // A file without EXIF data is readable, but a missing file isn't.
+        repo.add_all(&vec![
+            scanned(library.join("Cargo.toml")),
+            scanned(library.join("missing.jpg")),
+        ])
+        .unwrap();
+
+        let pics = repo.find_need_metadata_update().unwrap();
+        let missing = pics
+            .iter()
+            .find(|pic| pic.path.ends_with("missing.jpg"))
+            .map(|pic| pic.picture_id)
+            .unwrap();
+
+        PhotoEnrich::enrich_pictures(&AtomicBool::new(false), &mut repo, &pics).unwrap();
+
+        // Unreadable picture is still shown and will be retried.
+        let pics = repo.find_need_metadata_update().unwrap();
+        assert_eq!(1, pics.len());
+        assert_eq!(missing, pics[0].picture_id);
+        assert_eq!(2, repo.all().unwrap().len());
+
+        assert_eq!(
+            Some(quarantine::Stage::Metadata),
+            repo.quarantined(missing).map(|(stage, _)| stage)
+        );
+    }
+}
@@ -3,6 +3,8 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use anyhow::*;
+use fotema_core::photo::PictureStore;
+use fotema_core::quarantine;
 use rayon::prelude::*;
 use relm4::prelude::*;
 use relm4::Reducer;
@@ -43,7 +45,7 @@ pub struct PhotoExtractMotion {
 impl PhotoExtractMotion {
     fn extract(
         stop: Arc<AtomicBool>,
-        repo: fotema_core::photo::Repository,
+        repo: impl PictureStore + Clone,
         extractor: fotema_core::photo::MotionPhotoExtractor,
         progress_monitor: Arc<Reducer<ProgressMonitor>>,
         sender: ComponentSender<Self>,
@@ -91,7 +93,11 @@ impl PhotoExtractMotion {
                             "Failed extracting motion photo: {:?}: Photo path: {:?}",
                             e, photo.path
                         );
-                        repo.clone().mark_broken(&photo.picture_id)
+                        repo.clone().mark_broken(
+                            &photo.picture_id,
+                            quarantine::Stage::MotionPhoto,
+                            &e.to_string(),
+                        )
                     }
                 };
 
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use anyhow::*;
+use fotema_core::quarantine;
 use futures::executor::block_on;
 use rayon::prelude::*;
 use relm4::prelude::*;
@@ -105,13 +106,21 @@ impl PhotoThumbnail {
                         "Failed generate or add thumbnail: {:?}: Photo path: {:?}",
                         e, pic.path
                     );
-                    let _ = repo.clone().mark_broken(&pic.picture_id);
+                    let _ = repo.clone().mark_broken(
+                        &pic.picture_id,
+                        quarantine::Stage::Thumbnail,
+                        &e.to_string(),
+                    );
                 } else if result.is_err() {
                     error!(
                         "Panicked generate or add thumbnail: Photo path: {:?}",
                         pic.path
                     );
-                    let _ = repo.clone().mark_broken(&pic.picture_id);
+                    let _ = repo.clone().mark_broken(
+                        &pic.picture_id,
+                        quarantine::Stage::Thumbnail,
+                        "Panicked generating thumbnail",
+                    );
                 }
 
                 progress_monitor.emit(ProgressMonitorInput::Advance);
@@ -39,7 +39,9 @@ impl VideoClean {
         let start = std::time::Instant::now();
 
         // Scrub vids from database if they no longer exist on the file system.
-        let vids: Vec<fotema_core::video::model::Video> = self.repo.all()?;
+        // Broken vids aren't shown, but would otherwise be kept forever.
+        let mut vids: Vec<fotema_core::video::model::Video> = self.repo.all()?;
+        vids.extend(self.repo.find_broken()?);
 
         info!("Found {} videos as candidates for cleaning", vids.len());
 
@@ -3,7 +3,9 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use anyhow::*;
+use fotema_core::quarantine;
 use fotema_core::video::metadata;
+use rayon::iter::Either;
 use rayon::prelude::*;
 use relm4::prelude::*;
 use relm4::shared_state::Reducer;
@@ -11,6 +13,7 @@ use relm4::Worker;
 
 use tracing::{error, info};
 
+use std::result::Result::Ok;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
@@ -67,18 +70,28 @@ impl VideoEnrich {
             count,
         ));
 
-        let metadatas = unprocessed
+        let (metadatas, failures): (Vec<_>, Vec<_>) = unprocessed
             .par_iter()
             .take_any_while(|_| !stop.load(Ordering::Relaxed))
-            .flat_map(|vid| {
+            .map(|vid| {
                 let result = metadata::from_path(&vid.path);
                 progress_monitor.emit(ProgressMonitorInput::Advance);
-                result.map(|m| (vid.video_id, m))
+                (vid.video_id, result)
             })
-            .collect();
+            .partition_map(|(video_id, result)| match result {
+                Ok(m) => Either::Left((video_id, m)),
+                Err(e) => Either::Right((video_id, e)),
+            });
 
         repo.add_metadata(metadatas)?;
 
+        // List videos we couldn't read as problem files, but keep showing them and
+        // retry them next time, because the failure might only be temporary.
+        for (video_id, e) in failures {
+            error!("Failed extracting metadata for {}: {:?}", video_id, e);
+            repo.quarantine(&video_id, quarantine::Stage::Metadata, &e.to_string())?;
+        }
+
         progress_monitor.emit(ProgressMonitorInput::Complete);
 
         info!(
@@ -13,6 +13,7 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tracing::{error, info};
 
+use fotema_core::quarantine;
 use fotema_core::video::{Repository, Thumbnailer, Video};
 
 use crate::app::components::progress_monitor::{
@@ -103,13 +104,21 @@ impl VideoThumbnail {
                         "Failed generate or add thumbnail: {:?}: Video path: {:?}",
                         e, vid.path
                     );
-                    let _ = repo.clone().mark_broken(&vid.video_id);
+                    let _ = repo.clone().mark_broken(
+                        &vid.video_id,
+                        quarantine::Stage::Thumbnail,
+                        &e.to_string(),
+                    );
                 } else if result.is_err() {
                     error!(
                         "Panicked generate or add thumbnail: Video path: {:?}",
                         vid.path
                     );
-                    let _ = repo.clone().mark_broken(&vid.video_id);
+                    let _ = repo.clone().mark_broken(
+                        &vid.video_id,
+                        quarantine::Stage::Thumbnail,
+                        "Panicked generating thumbnail",
+                    );
                 }
 
                 progress_monitor.emit(ProgressMonitorInput::Advance);
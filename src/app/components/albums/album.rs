@@ -8,18 +8,17 @@ use fotema_core::YearMonth;
 use gtk::prelude::OrientableExt;
 use relm4::binding::*;
 use relm4::gtk;
-use relm4::gtk::gdk;
-use relm4::gtk::gdk_pixbuf;
+use relm4::gtk::gio;
 use relm4::gtk::prelude::AdjustmentExt;
 use relm4::gtk::prelude::*;
 use relm4::typed_view::grid::{RelmGridItem, TypedGridView};
 use relm4::*;
-use std::path::Path;
 use std::sync::Arc;
 use strum::IntoEnumIterator;
 
 use super::album_filter::AlbumFilter;
 use super::album_sort::AlbumSort;
+use super::thumbnail;
 use crate::app::adaptive;
 use crate::app::ActiveView;
 use crate::app::SharedState;
@@ -89,6 +88,9 @@ struct PhotoGridItemWidgets {
 
     // If the gtk::Picture has been bound to edge_length.
     is_bound: bool,
+
+    // Cancels loading of thumbnail when tile is unbound.
+    thumbnail_load: Option<gio::Cancellable>,
 }
 
 impl RelmGridItem for PhotoGridItem {
@@ -149,6 +151,7 @@ impl RelmGridItem for PhotoGridItem {
             duration_overlay,
             duration_label,
             is_bound: false,
+            thumbnail_load: None,
         };
 
         (root, widgets)
@@ -173,25 +176,11 @@ impl RelmGridItem for PhotoGridItem {
             widgets.is_bound = true;
         }
 
-        if self
-            .visual
-            .thumbnail_path
-            .as_ref()
-            .is_some_and(|x| x.exists())
-        {
-            widgets
-                .picture
-                .set_filename(self.visual.thumbnail_path.clone());
+        if let Some(ref thumbnail_path) = self.visual.thumbnail_path {
+            let load = thumbnail::load(&widgets.picture, thumbnail_path);
+            widgets.thumbnail_load = Some(load);
         } else {
-            let pb = gdk_pixbuf::Pixbuf::from_resource_at_scale(
-                "/app/fotema/Fotema/icons/scalable/actions/image-missing-symbolic.svg",
-                200,
-                200,
-                true,
-            )
-            .unwrap();
-            let img = gdk::Texture::for_pixbuf(&pb);
-            widgets.picture.set_paintable(Some(&img));
+            widgets.picture.set_paintable(Some(&thumbnail::missing()));
         }
 
         if self.visual.is_motion_photo() {
@@ -226,7 +215,7 @@ impl RelmGridItem for PhotoGridItem {
     }
 
     fn unbind(&mut self, widgets: &mut Self::Widgets, _root: &mut Self::Root) {
-        widgets.picture.set_filename(None::<&Path>);
+        thumbnail::unload(&widgets.picture, widgets.thumbnail_load.take());
         widgets.motion_type_icon.set_icon_name(None);
         widgets.status_overlay.set_visible(false);
         widgets.duration_overlay.set_visible(false);
@@ -4,6 +4,7 @@
 
 use std::path::PathBuf;
 
+use fotema_core::event::Event;
 use fotema_core::PictureId;
 use fotema_core::Visual;
 use fotema_core::VisualId;
@@ -37,6 +38,9 @@ pub enum AlbumFilter {
     // Show photos in a geographic area
     GeographicArea(CellIndex),
 
+    // Show photos taken during a trip or event
+    Event(Event),
+
     /// Show photos who's picture_id is in a set. Used for person filtering.
     /// FIXME should probably be a Set of some kind... but that mucks up PartialEq and Eq.
     Any(Vec<PictureId>),
@@ -60,6 +64,7 @@ impl AlbumFilter {
                     false
                 }
             }
+            AlbumFilter::Event(event) => event.contains(v.ordering_ts),
             AlbumFilter::Any(picture_ids) => {
                 v.picture_id.is_some_and(|id| picture_ids.contains(&id))
             }
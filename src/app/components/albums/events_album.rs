@@ -0,0 +1,388 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use relm4::adw::{self, prelude::*};
+use relm4::gtk;
+use relm4::prelude::*;
+use relm4::*;
+
+use crate::app::ActiveView;
+use crate::app::SharedState;
+use crate::app::ViewName;
+use crate::fl;
+
+use fotema_core::event;
+use fotema_core::event::{Event, EventId};
+use fotema_core::Visual;
+use fotema_core::VisualId;
+
+use chrono::{DateTime, Datelike, Utc};
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use tracing::{error, info};
+
+const THUMBNAIL_EDGE_LENGTH: i32 = 64;
+
+/// What grouping into events depends on for one item: its ID, time, and location.
+type ClusterKey = (VisualId, DateTime<Utc>, Option<(f64, f64)>);
+
+#[derive(Debug)]
+pub enum EventsAlbumInput {
+    /// Events view is visible.
+    Activate,
+
+    /// Library has changed, so group items into events again.
+    Refresh,
+
+    /// Items have been grouped into events in the background.
+    Grouped(Vec<ClusterKey>),
+
+    /// User has selected an event. usize is index into vector of events.
+    Selected(usize),
+
+    /// Ask user for new name for event.
+    RenameDialog(EventId),
+
+    /// Rename an event. An empty name restores the automatic title.
+    Rename(EventId, String),
+}
+
+#[derive(Debug)]
+pub enum EventsAlbumOutput {
+    /// User has selected an event to view as an album.
+    Selected(Event),
+}
+
+pub struct EventsAlbum {
+    state: SharedState,
+
+    active_view: ActiveView,
+
+    repo: event::Repository,
+
+    /// Events, newest first. MUST be in same order as events_list.
+    events: Vec<Event>,
+
+    /// Items as they were when last grouped into events. Grouping is slow for
+    /// a big library, so only group again when these change. None until first grouped.
+    clustered: Option<Vec<ClusterKey>>,
+
+    /// Items are being grouped into events in the background.
+    is_grouping: bool,
+
+    events_list: gtk::ListBox,
+
+    events_page: gtk::ScrolledWindow,
+
+    status: adw::StatusPage,
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for EventsAlbum {
+    type Init = (SharedState, ActiveView, event::Repository);
+    type Input = EventsAlbumInput;
+    type Output = EventsAlbumOutput;
+
+    view! {
+        gtk::Box {
+            set_orientation: gtk::Orientation::Vertical,
+
+            #[local_ref]
+            events_page -> gtk::ScrolledWindow {
+                set_vexpand: true,
+
+                adw::Clamp {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_maximum_size: 800,
+
+                    #[local_ref]
+                    events_list -> gtk::ListBox {
+                        set_margin_all: 12,
+                        set_valign: gtk::Align::Start,
+
+                        connect_row_activated[sender] => move |_, row| {
+                            if let Ok(index) = usize::try_from(row.index()) {
+                                sender.input(EventsAlbumInput::Selected(index));
+                            }
+                        },
+                    },
+                }
+            },
+
+            #[local_ref]
+            status -> adw::StatusPage {
+                set_valign: gtk::Align::Start,
+                set_vexpand: true,
+                set_visible: false,
+
+                set_icon_name: Some("image-alt-symbolic"),
+                set_title: &fl!("events-page-status-none", "title"),
+                set_description: Some(&fl!("events-page-status-none", "description")),
+            },
+        }
+    }
+
+    fn init(
+        (state, active_view, repo): Self::Init,
+        _root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let events_list = gtk::ListBox::builder()
+            .css_classes(["boxed-list"])
+            .selection_mode(gtk::SelectionMode::None)
+            .build();
+
+        let events_page = gtk::ScrolledWindow::new();
+
+        let status = adw::StatusPage::new();
+
+        let model = EventsAlbum {
+            state,
+            active_view,
+            repo,
+            events: vec![],
+            clustered: None,
+            is_grouping: false,
+            events_list: events_list.clone(),
+            events_page: events_page.clone(),
+            status: status.clone(),
+        };
+
+        let widgets = view_output!();
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
+        match msg {
+            EventsAlbumInput::Activate => {
+                *self.active_view.write() = ViewName::Events;
+                self.refresh(&sender);
+            }
+            EventsAlbumInput::Refresh => {
+                if *self.active_view.read() == ViewName::Events {
+                    self.refresh(&sender);
+                }
+            }
+            EventsAlbumInput::Grouped(keys) => {
+                self.is_grouping = false;
+                self.clustered = Some(keys);
+                if *self.active_view.read() == ViewName::Events {
+                    self.refresh(&sender);
+                }
+            }
+            EventsAlbumInput::Selected(index) => {
+                if let Some(event) = self.events.get(index) {
+                    let _ = sender.output(EventsAlbumOutput::Selected(event.clone()));
+                }
+            }
+            EventsAlbumInput::RenameDialog(event_id) => {
+                let Some(event) = self.events.iter().find(|e| e.event_id == event_id) else {
+                    return;
+                };
+
+                let event_name = gtk::Entry::builder()
+                    .placeholder_text(self.title(event))
+                    .build();
+                event_name.set_text(event.name.as_deref().unwrap_or_default());
+
+                let dialog = adw::AlertDialog::builder()
+                    .heading(fl!("event-rename-dialog", "heading"))
+                    .body(fl!("event-rename-dialog", "body"))
+                    .close_response("cancel")
+                    .default_response("rename")
+                    .extra_child(&event_name)
+                    .build();
+
+                dialog.add_response("cancel", &fl!("event-rename-dialog", "cancel-button"));
+
+                dialog.add_response("rename", &fl!("event-rename-dialog", "rename-button"));
+                dialog.set_response_appearance("rename", adw::ResponseAppearance::Suggested);
+
+                {
+                    let event_name = event_name.clone();
+                    let sender = sender.clone();
+                    dialog.connect_response(None, move |_, response| {
+                        if response == "rename" {
+                            let name = event_name.text();
+                            sender.input(EventsAlbumInput::Rename(event_id, name.into()));
+                        }
+                    });
+                }
+
+                {
+                    let event_name = event_name.clone();
+                    let sender = sender.clone();
+                    let dialog = dialog.clone();
+                    event_name.clone().connect_activate(move |_| {
+                        dialog.close();
+                        let name = event_name.text();
+                        sender.input(EventsAlbumInput::Rename(event_id, name.into()));
+                    });
+                }
+
+                if let Some(root) = self.events_list.root() {
+                    dialog.present(Some(&root));
+                    event_name.grab_focus();
+                } else {
+                    error!("Couldn't get root widget!");
+                }
+            }
+            EventsAlbumInput::Rename(event_id, name) => {
+                let name = name.trim();
+                let name = (!name.is_empty()).then_some(name);
+
+                info!("Renaming event {} to {:?}", event_id, name);
+
+                if let Err(e) = self.repo.rename(event_id, name) {
+                    error!("Failed to rename event: {}", e);
+                    return;
+                }
+
+                self.refresh(&sender);
+            }
+        }
+    }
+}
+
+impl EventsAlbum {
+    /// Title for event. Either the name given by the user, or a title made from
+    /// the place and the month the event started in, such as "Lisbon, March 2023".
+    fn title(&self, event: &Event) -> String {
+        if let Some(ref name) = event.name {
+            return name.clone();
+        }
+
+        let date = fl!(
+            "month-thumbnail-label",
+            month = event.start_ts.month(),
+            year = event.start_ts.year().to_string()
+        );
+
+        match event.place {
+            Some(ref place) => fl!("event-title-place", place = place.clone(), date = date),
+            None => date,
+        }
+    }
+
+    fn refresh(&mut self, sender: &ComponentSender<Self>) {
+        let visuals: Vec<Arc<Visual>> = self.state.read().clone();
+
+        // Group again when items have been added, removed, or had their time or location
+        // changed, because they might join or split events.
+        let keys: Vec<ClusterKey> = visuals
+            .iter()
+            .map(|v| {
+                (
+                    v.visual_id.clone(),
+                    v.ordering_ts,
+                    v.location.map(|l| (l.lat(), l.lng())),
+                )
+            })
+            .collect();
+
+        // Grouping is slow for a big library, so group in the background and refresh
+        // again when done. Until then, show the events from the last grouping.
+        if self.clustered.as_ref() != Some(&keys) && !self.is_grouping {
+            info!("Grouping {} items into events", visuals.len());
+            self.is_grouping = true;
+
+            let mut repo = self.repo.clone();
+            let visuals = visuals.clone();
+            let sender = sender.clone();
+            std::thread::spawn(move || {
+                let clusters = event::cluster::cluster(&visuals);
+                if let Err(e) = repo.update(&clusters) {
+                    error!("Failed updating events: {}", e);
+                }
+                sender.input(EventsAlbumInput::Grouped(keys));
+            });
+        }
+
+        self.events = match self.repo.all() {
+            Ok(events) => events,
+            Err(e) => {
+                error!("Failed loading events: {}", e);
+                vec![]
+            }
+        };
+
+        // Timeline of newest events first
+        self.events.reverse();
+
+        // Count items and pick a thumbnail for every event in one pass over the items.
+        // Events don't overlap, so an item can only be in the newest event that
+        // started before it.
+        let mut counts = vec![0; self.events.len()];
+        let mut thumbnails: Vec<Option<&PathBuf>> = vec![None; self.events.len()];
+        for visual in visuals.iter() {
+            let index = self
+                .events
+                .partition_point(|e| e.start_ts > visual.ordering_ts);
+            if self
+                .events
+                .get(index)
+                .is_some_and(|e| e.contains(visual.ordering_ts))
+            {
+                counts[index] += 1;
+                thumbnails[index] = thumbnails[index].or(visual.thumbnail_path.as_ref());
+            }
+        }
+
+        self.events_list.remove_all();
+
+        for (index, event) in self.events.iter().enumerate() {
+            let days = (event.end_ts.date_naive() - event.start_ts.date_naive()).num_days() + 1;
+
+            let row = adw::ActionRow::builder()
+                .title(gtk::glib::markup_escape_text(&self.title(event)))
+                .subtitle(fl!(
+                    "events-page-subtitle",
+                    days = days,
+                    count = counts[index]
+                ))
+                .activatable(true)
+                .build();
+
+            let thumbnail = gtk::Picture::builder()
+                .width_request(THUMBNAIL_EDGE_LENGTH)
+                .height_request(THUMBNAIL_EDGE_LENGTH)
+                .content_fit(gtk::ContentFit::Cover)
+                .margin_top(6)
+                .margin_bottom(6)
+                .build();
+
+            if let Some(thumbnail_path) = thumbnails[index] {
+                thumbnail.set_filename(Some(thumbnail_path));
+            }
+
+            let rename_button = gtk::Button::builder()
+                .valign(gtk::Align::Center)
+                .icon_name("document-edit-symbolic")
+                .tooltip_text(fl!("events-page", "rename"))
+                .css_classes(["flat"])
+                .build();
+
+            {
+                let sender = sender.clone();
+                let event_id = event.event_id;
+                rename_button.connect_clicked(move |_| {
+                    sender.input(EventsAlbumInput::RenameDialog(event_id))
+                });
+            }
+
+            row.add_prefix(&thumbnail);
+            row.add_suffix(&rename_button);
+
+            self.events_list.append(&row);
+        }
+
+        info!("Showing {} events", self.events.len());
+
+        self.status.set_visible(self.events.is_empty());
+        self.events_page.set_visible(!self.events.is_empty());
+    }
+}
@@ -5,9 +5,12 @@
 pub mod album;
 pub mod album_filter;
 pub mod album_sort;
+pub mod events_album;
 pub mod folders_album;
 pub mod months_album;
 pub mod people_album;
 pub mod person_album;
 pub mod places_album;
+pub mod search_album;
+pub mod thumbnail;
 pub mod years_album;
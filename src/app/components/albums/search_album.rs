@@ -0,0 +1,205 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use fotema_core::VisualId;
+use gtk::prelude::OrientableExt;
+use relm4::adw;
+use relm4::adw::prelude::*;
+use relm4::gtk;
+use relm4::*;
+
+use crate::app::adaptive;
+use crate::app::components::albums::{
+    album::{Album, AlbumInput, AlbumOutput},
+    album_filter::AlbumFilter,
+    album_sort::AlbumSort,
+};
+use crate::app::ActiveView;
+use crate::app::SharedState;
+use crate::app::ViewName;
+
+use crate::fl;
+use fotema_core::caption;
+use fotema_core::PictureId;
+
+use tracing::{error, info};
+
+#[derive(Debug)]
+pub enum SearchAlbumInput {
+    /// Album is visible
+    Activate,
+
+    // State has been updated
+    Refresh,
+
+    /// Search text has changed
+    Search(String),
+
+    /// Adapt to layout
+    Adapt(adaptive::Layout),
+
+    /// Picture selected in underlying album
+    Selected(VisualId),
+
+    Sort(AlbumSort),
+
+    // Ignore event
+    Ignore,
+}
+
+#[derive(Debug)]
+pub enum SearchAlbumOutput {
+    /// User has selected photo or video in grid view
+    Selected(VisualId, AlbumFilter),
+}
+
+/// Pictures with titles, captions, or descriptions matching a search.
+pub struct SearchAlbum {
+    repo: caption::Repository,
+    query: String,
+    picture_ids: Vec<PictureId>,
+    album: Controller<Album>,
+    search_entry: gtk::SearchEntry,
+    active_view: ActiveView,
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for SearchAlbum {
+    type Init = (SharedState, caption::Repository, ActiveView);
+    type Input = SearchAlbumInput;
+    type Output = SearchAlbumOutput;
+
+    view! {
+        adw::ToolbarView {
+            add_top_bar = &adw::HeaderBar {
+                #[wrap(Some)]
+                #[local_ref]
+                set_title_widget = &search_entry -> gtk::SearchEntry {
+                    set_placeholder_text: Some(&fl!("search-album", "placeholder")),
+                    set_hexpand: true,
+                    connect_search_changed[sender] => move |entry| {
+                        sender.input(SearchAlbumInput::Search(entry.text().to_string()));
+                    },
+                },
+            },
+
+            #[wrap(Some)]
+            set_content = &gtk::Box {
+                set_orientation: gtk::Orientation::Vertical,
+                set_vexpand: true,
+
+                adw::StatusPage {
+                    set_valign: gtk::Align::Start,
+                    set_vexpand: true,
+                    set_icon_name: Some("system-search-symbolic"),
+
+                    #[watch]
+                    set_visible: model.picture_ids.is_empty(),
+
+                    #[watch]
+                    set_title: &if model.query.trim().is_empty() {
+                        fl!("search-album-status-empty", "title")
+                    } else {
+                        fl!("search-album-status-no-results", "title")
+                    },
+
+                    #[watch]
+                    set_description: Some(&if model.query.trim().is_empty() {
+                        fl!("search-album-status-empty", "description")
+                    } else {
+                        fl!("search-album-status-no-results", "description")
+                    }),
+                },
+
+                gtk::Box {
+                    set_vexpand: true,
+
+                    #[watch]
+                    set_visible: !model.picture_ids.is_empty(),
+
+                    container_add: model.album.widget(),
+                },
+            }
+        }
+    }
+
+    fn init(
+        (state, repo, active_view): Self::Init,
+        _root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let album = Album::builder()
+            .launch((
+                state.clone(),
+                active_view.clone(),
+                ViewName::Search,
+                AlbumFilter::None,
+            ))
+            .forward(sender.input_sender(), |msg| match msg {
+                AlbumOutput::Selected(id, _) => SearchAlbumInput::Selected(id),
+                AlbumOutput::ScrollOffset(_) => SearchAlbumInput::Ignore,
+            });
+
+        let search_entry = gtk::SearchEntry::new();
+
+        let model = SearchAlbum {
+            repo,
+            query: String::new(),
+            picture_ids: vec![],
+            album,
+            search_entry: search_entry.clone(),
+            active_view,
+        };
+
+        let widgets = view_output!();
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
+        match msg {
+            SearchAlbumInput::Activate => {
+                *self.active_view.write() = ViewName::Search;
+                self.album.sender().emit(AlbumInput::Activate);
+                self.search_entry.grab_focus();
+            }
+            SearchAlbumInput::Refresh => {
+                self.album.sender().emit(AlbumInput::Refresh);
+            }
+            SearchAlbumInput::Sort(sort) => {
+                self.album.sender().emit(AlbumInput::Sort(sort));
+            }
+            SearchAlbumInput::Search(query) => {
+                self.picture_ids = self.repo.search(&query).unwrap_or_else(|e| {
+                    error!("Failed searching captions: {}", e);
+                    vec![]
+                });
+                info!(
+                    "Search for {:?} found {} items",
+                    query,
+                    self.picture_ids.len()
+                );
+                self.query = query;
+
+                self.album
+                    .sender()
+                    .emit(AlbumInput::Filter(AlbumFilter::Any(
+                        self.picture_ids.clone(),
+                    )));
+                self.album.sender().emit(AlbumInput::ScrollToTop);
+            }
+            SearchAlbumInput::Selected(visual_id) => {
+                let _ = sender.output(SearchAlbumOutput::Selected(
+                    visual_id,
+                    AlbumFilter::Any(self.picture_ids.clone()),
+                ));
+            }
+            SearchAlbumInput::Adapt(layout) => {
+                // FIXME album should directly subscribe to layout state.
+                self.album.sender().emit(AlbumInput::Adapt(layout));
+            }
+            SearchAlbumInput::Ignore => {}
+        }
+    }
+}
@@ -0,0 +1,223 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// Loading of thumbnails for photo grid tiles.
+//
+// Reading thumbnails on the main thread stalls scrolling when the library is on a
+// spinning disk or a network mount. Instead, a tile shows a placeholder straight
+// away and the thumbnail is read and decoded on a worker thread. The thumbnail
+// fades in when it is ready. See photo-grid-thumbnail-* in style.css.
+//
+// The placeholder is a blurred micro-preview if the thumbnail has been loaded
+// before, such as when scrolling back or switching between the day, month, and
+// year views. Micro-previews are tiny, so many can be kept in memory. Otherwise
+// the placeholder is a neutral tint.
+//
+// If the tile is unbound (scrolled away) before the thumbnail is read, the load
+// is cancelled so the disk isn't kept busy with thumbnails that will never be seen.
+
+use relm4::gtk;
+use relm4::gtk::gdk;
+use relm4::gtk::gdk_pixbuf;
+use relm4::gtk::gio;
+use relm4::gtk::glib;
+use relm4::gtk::prelude::*;
+
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+
+use tracing::debug;
+
+/// CSS class for a tile's picture while its thumbnail is loading. Shows a placeholder.
+const LOADING_CSS_CLASS: &str = "photo-grid-thumbnail-loading";
+
+/// CSS class for a tile's picture while showing a micro-preview. Blurs the preview.
+const PREVIEW_CSS_CLASS: &str = "photo-grid-thumbnail-preview";
+
+/// CSS class for a tile's picture when its thumbnail has loaded. Fades in the thumbnail.
+const LOADED_CSS_CLASS: &str = "photo-grid-thumbnail-loaded";
+
+/// Width and height of a micro-preview in pixels.
+const PREVIEW_EDGE_LENGTH: usize = 8;
+
+/// Most micro-previews to keep in memory. Oldest are forgotten first.
+const MAX_PREVIEWS: usize = 20_000;
+
+/// Micro-previews of thumbnails that have already been loaded.
+#[derive(Default)]
+struct Previews {
+    textures: HashMap<PathBuf, gdk::Texture>,
+
+    /// Thumbnail paths in the order their micro-previews were added.
+    order: VecDeque<PathBuf>,
+}
+
+impl Previews {
+    fn get(&self, thumbnail_path: &Path) -> Option<gdk::Texture> {
+        self.textures.get(thumbnail_path).cloned()
+    }
+
+    fn insert(&mut self, thumbnail_path: PathBuf, preview: gdk::Texture) {
+        if self
+            .textures
+            .insert(thumbnail_path.clone(), preview)
+            .is_some()
+        {
+            return;
+        }
+
+        self.order.push_back(thumbnail_path);
+
+        if self.order.len() > MAX_PREVIEWS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.textures.remove(&oldest);
+            }
+        }
+    }
+}
+
+thread_local! {
+    // Only used from the main thread.
+    static PREVIEWS: RefCell<Previews> = RefCell::new(Previews::default());
+}
+
+/// Start loading a thumbnail into a tile's picture.
+/// The returned cancellable must be cancelled when the tile is unbound.
+pub fn load(picture: &gtk::Picture, thumbnail_path: &Path) -> gio::Cancellable {
+    let cancellable = gio::Cancellable::new();
+
+    let preview = PREVIEWS.with_borrow(|previews| previews.get(thumbnail_path));
+
+    picture.set_paintable(preview.as_ref());
+    picture.remove_css_class(LOADED_CSS_CLASS);
+    if preview.is_some() {
+        picture.add_css_class(PREVIEW_CSS_CLASS);
+    } else {
+        picture.add_css_class(LOADING_CSS_CLASS);
+    }
+
+    let has_preview = preview.is_some();
+    let thumbnail_path = thumbnail_path.to_path_buf();
+    let picture = picture.clone();
+    let load_cancellable = cancellable.clone();
+    let bind_cancellable = cancellable.clone();
+
+    glib::spawn_future_local(async move {
+        let load_path = thumbnail_path.clone();
+        let result = gio::spawn_blocking(move || {
+            // Tile might have scrolled away while waiting for a worker thread.
+            if load_cancellable.is_cancelled() {
+                return None;
+            }
+            let texture = gdk::Texture::from_filename(&load_path)
+                .inspect_err(|e| debug!("Failed loading thumbnail {:?}: {}", load_path, e))
+                .ok()?;
+            let preview = (!has_preview).then(|| micro_preview(&texture));
+            Some((texture, preview))
+        })
+        .await;
+
+        let texture = match result {
+            Ok(Some((texture, preview))) => {
+                // Keep the micro-preview even if the tile has been unbound,
+                // because it will be wanted when the tile is scrolled back.
+                if let Some(preview) = preview {
+                    PREVIEWS.with_borrow_mut(|previews| previews.insert(thumbnail_path, preview));
+                }
+                Some(texture)
+            }
+            _ => None,
+        };
+
+        // Tile has been unbound and possibly bound to a different item.
+        if bind_cancellable.is_cancelled() {
+            return;
+        }
+
+        let texture = texture.unwrap_or_else(missing);
+
+        picture.set_paintable(Some(&texture));
+        picture.remove_css_class(LOADING_CSS_CLASS);
+        picture.remove_css_class(PREVIEW_CSS_CLASS);
+        picture.add_css_class(LOADED_CSS_CLASS);
+    });
+
+    cancellable
+}
+
+/// Shrink a thumbnail to a tiny texture by averaging blocks of pixels.
+/// Scaled up and blurred, it gives an impression of the picture's colours.
+fn micro_preview(texture: &gdk::Texture) -> gdk::Texture {
+    let format = gdk::MemoryFormat::R8g8b8a8Premultiplied;
+
+    let mut downloader = gdk::TextureDownloader::new(texture);
+    downloader.set_format(format);
+    let (bytes, stride) = downloader.download_bytes();
+
+    let width = texture.width().max(0) as usize;
+    let height = texture.height().max(0) as usize;
+
+    let mut pixels = vec![0u8; PREVIEW_EDGE_LENGTH * PREVIEW_EDGE_LENGTH * 4];
+
+    if width > 0 && height > 0 {
+        for preview_y in 0..PREVIEW_EDGE_LENGTH {
+            let y0 = preview_y * height / PREVIEW_EDGE_LENGTH;
+            let y1 = ((preview_y + 1) * height / PREVIEW_EDGE_LENGTH).clamp(y0 + 1, height);
+
+            for preview_x in 0..PREVIEW_EDGE_LENGTH {
+                let x0 = preview_x * width / PREVIEW_EDGE_LENGTH;
+                let x1 = ((preview_x + 1) * width / PREVIEW_EDGE_LENGTH).clamp(x0 + 1, width);
+
+                let mut sum = [0usize; 4];
+                for y in y0..y1 {
+                    for x in x0..x1 {
+                        let offset = y * stride + x * 4;
+                        for (channel, total) in sum.iter_mut().enumerate() {
+                            *total += bytes[offset + channel] as usize;
+                        }
+                    }
+                }
+
+                let count = (y1 - y0) * (x1 - x0);
+                let offset = (preview_y * PREVIEW_EDGE_LENGTH + preview_x) * 4;
+                for (channel, total) in sum.iter().enumerate() {
+                    pixels[offset + channel] = (total / count) as u8;
+                }
+            }
+        }
+    }
+
+    gdk::MemoryTexture::new(
+        PREVIEW_EDGE_LENGTH as i32,
+        PREVIEW_EDGE_LENGTH as i32,
+        format,
+        &glib::Bytes::from_owned(pixels),
+        PREVIEW_EDGE_LENGTH * 4,
+    )
+    .upcast()
+}
+
+/// Icon for an item without a thumbnail.
+pub fn missing() -> gdk::Texture {
+    let pb = gdk_pixbuf::Pixbuf::from_resource_at_scale(
+        "/app/fotema/Fotema/icons/scalable/actions/image-missing-symbolic.svg",
+        200,
+        200,
+        true,
+    )
+    .unwrap();
+    gdk::Texture::for_pixbuf(&pb)
+}
+
+/// Cancel loading a thumbnail, if it hasn't already loaded, and clear the picture.
+pub fn unload(picture: &gtk::Picture, cancellable: Option<gio::Cancellable>) {
+    if let Some(cancellable) = cancellable {
+        cancellable.cancel();
+    }
+    picture.set_paintable(None::<&gdk::Paintable>);
+    picture.remove_css_class(LOADING_CSS_CLASS);
+    picture.remove_css_class(PREVIEW_CSS_CLASS);
+    picture.remove_css_class(LOADED_CSS_CLASS);
+}
@@ -13,18 +13,17 @@ use itertools::Itertools;
 
 use relm4::binding::*;
 use relm4::gtk;
-use relm4::gtk::gdk;
-use relm4::gtk::gdk_pixbuf;
+use relm4::gtk::gio;
 use relm4::gtk::prelude::FrameExt;
 use relm4::gtk::prelude::WidgetExt;
 use relm4::typed_view::grid::{RelmGridItem, TypedGridView};
 use relm4::*;
 
-use std::path;
 use std::sync::Arc;
 
 use tracing::info;
 
+use super::thumbnail;
 use crate::adaptive;
 use crate::app::ActiveView;
 use crate::app::AlbumSort;
@@ -68,6 +67,9 @@ struct Widgets {
 
     // If the gtk::Picture has been bound to edge_length.
     is_bound: bool,
+
+    // Cancels loading of thumbnail when tile is unbound.
+    thumbnail_load: Option<gio::Cancellable>,
 }
 
 impl RelmGridItem for PhotoGridItem {
@@ -109,6 +111,7 @@ impl RelmGridItem for PhotoGridItem {
             picture,
             label,
             is_bound: false,
+            thumbnail_load: None,
         };
 
         (root, widgets)
@@ -133,30 +136,16 @@ impl RelmGridItem for PhotoGridItem {
             widgets.is_bound = true;
         }
 
-        if self
-            .picture
-            .thumbnail_path
-            .as_ref()
-            .is_some_and(|x| x.exists())
-        {
-            widgets
-                .picture
-                .set_filename(self.picture.thumbnail_path.clone());
+        if let Some(ref thumbnail_path) = self.picture.thumbnail_path {
+            let load = thumbnail::load(&widgets.picture, thumbnail_path);
+            widgets.thumbnail_load = Some(load);
         } else {
-            let pb = gdk_pixbuf::Pixbuf::from_resource_at_scale(
-                "/app/fotema/Fotema/icons/scalable/actions/image-missing-symbolic.svg",
-                200,
-                200,
-                true,
-            )
-            .unwrap();
-            let img = gdk::Texture::for_pixbuf(&pb);
-            widgets.picture.set_paintable(Some(&img));
+            widgets.picture.set_paintable(Some(&thumbnail::missing()));
         }
     }
 
     fn unbind(&mut self, widgets: &mut Self::Widgets, _root: &mut Self::Root) {
-        widgets.picture.set_filename(None::<&path::Path>);
+        thumbnail::unload(&widgets.picture, widgets.thumbnail_load.take());
         // clear orientation transformation css classes
         for orient in PictureOrientation::iter() {
             widgets.picture.remove_css_class(orient.as_ref());
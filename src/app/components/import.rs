@@ -0,0 +1,356 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use relm4::adw::{self, prelude::*};
+use relm4::gtk::{self, glib};
+use relm4::prelude::*;
+use relm4::*;
+
+use crate::app::LibraryAvailability;
+use crate::app::SettingsState;
+use crate::fl;
+
+use fotema_core::import::{template, Organizer, Placement, Summary, Template, Transfer};
+use fotema_core::network_share::Availability;
+
+use std::path::PathBuf;
+
+use tracing::{error, info};
+
+/// Most files to list in the preview. Listing every file on a full memory card
+/// would make the dialog slow to open.
+const MAX_PREVIEW_ROWS: usize = 1000;
+
+#[derive(Debug)]
+pub enum ImportInput {
+    /// Work out where files and folders dropped by the user would go, and
+    /// show that to the user without changing anything.
+    Preview(Vec<PathBuf>),
+
+    /// Destinations for files have been worked out.
+    Planned(Vec<Placement>),
+
+    /// User has accepted the preview, so copy or move the files.
+    Import,
+
+    /// Files have been copied or moved into the library.
+    Imported(Summary),
+}
+
+#[derive(Debug)]
+pub enum ImportOutput {
+    /// Files have been imported into the library, which should be scanned again.
+    Imported,
+}
+
+pub struct ImportDialog {
+    settings_state: SettingsState,
+
+    library_availability: LibraryAvailability,
+
+    parent: adw::ApplicationWindow,
+
+    dialog: adw::Dialog,
+
+    placements_list: gtk::ListBox,
+
+    /// Files and folders dropped by the user for the current preview.
+    paths: Vec<PathBuf>,
+
+    /// Files and folders dropped while busy, to be previewed when no longer busy.
+    queued_paths: Vec<PathBuf>,
+
+    /// Organizer for current import. None until files have been planned.
+    organizer: Option<Organizer>,
+
+    /// Copy or move, fixed when the preview is made so that the preview
+    /// describes what will happen.
+    transfer: Transfer,
+
+    placements: Vec<Placement>,
+
+    is_busy: bool,
+
+    description: String,
+}
+
+#[relm4::component(pub async)]
+impl SimpleAsyncComponent for ImportDialog {
+    type Init = (SettingsState, LibraryAvailability, adw::ApplicationWindow);
+    type Input = ImportInput;
+    type Output = ImportOutput;
+
+    view! {
+        adw::Dialog {
+            set_title: &fl!("import-dialog"),
+            set_content_width: 600,
+            set_content_height: 600,
+
+            #[wrap(Some)]
+            set_child = &adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar,
+
+                #[wrap(Some)]
+                set_content = &gtk::Box {
+                    set_orientation: gtk::Orientation::Vertical,
+                    set_spacing: 12,
+                    set_margin_all: 12,
+
+                    gtk::Label {
+                        set_wrap: true,
+                        set_justify: gtk::Justification::Center,
+
+                        #[watch]
+                        set_label: &model.description,
+                    },
+
+                    gtk::Spinner {
+                        #[watch]
+                        set_visible: model.is_busy,
+
+                        #[watch]
+                        set_spinning: model.is_busy,
+                    },
+
+                    gtk::ScrolledWindow {
+                        set_vexpand: true,
+                        set_propagate_natural_height: true,
+
+                        #[watch]
+                        set_visible: !model.placements.is_empty(),
+
+                        #[local_ref]
+                        placements_list -> gtk::ListBox {
+                            set_valign: gtk::Align::Start,
+                        },
+                    },
+
+                    gtk::Button {
+                        set_label: &fl!("import-dialog", "import"),
+                        set_halign: gtk::Align::Center,
+                        add_css_class: "pill",
+                        add_css_class: "suggested-action",
+
+                        #[watch]
+                        set_visible: model.organizer.is_some() && model.to_import() > 0,
+
+                        #[watch]
+                        set_sensitive: !model.is_busy,
+
+                        connect_clicked => ImportInput::Import,
+                    },
+                },
+            },
+        }
+    }
+
+    async fn init(
+        (settings_state, library_availability, parent): Self::Init,
+        dialog: Self::Root,
+        sender: AsyncComponentSender<Self>,
+    ) -> AsyncComponentParts<Self> {
+        let placements_list = gtk::ListBox::builder()
+            .css_classes(["boxed-list"])
+            .selection_mode(gtk::SelectionMode::None)
+            .build();
+
+        let model = Self {
+            settings_state,
+            library_availability,
+            parent,
+            dialog: dialog.clone(),
+            placements_list: placements_list.clone(),
+            paths: vec![],
+            queued_paths: vec![],
+            organizer: None,
+            transfer: Transfer::default(),
+            placements: vec![],
+            is_busy: false,
+            description: String::new(),
+        };
+
+        let widgets = view_output!();
+
+        AsyncComponentParts { model, widgets }
+    }
+
+    async fn update(&mut self, msg: Self::Input, sender: AsyncComponentSender<Self>) {
+        match msg {
+            ImportInput::Preview(paths) => {
+                self.dialog.present(Some(&self.parent));
+
+                if self.is_busy {
+                    info!("Import dialog is busy, so queueing {:?}", paths);
+                    self.queued_paths.extend(paths);
+                    return;
+                }
+
+                self.organizer = None;
+                self.placements.clear();
+                self.placements_list.remove_all();
+
+                if *self.library_availability.read() == Availability::Offline {
+                    self.description = fl!("import-dialog-offline");
+                    return;
+                }
+
+                let settings = self.settings_state.read().clone();
+
+                let template = if settings.organize_imports {
+                    Template::parse(&settings.import_template)
+                } else {
+                    Template::parse(template::FLAT)
+                };
+
+                let template = match template {
+                    Ok(template) => template,
+                    Err(e) => {
+                        error!("Invalid import template: {:?}", e);
+                        self.description = fl!("import-dialog-invalid-template");
+                        return;
+                    }
+                };
+
+                info!(
+                    "Previewing import of {:?} with template {}",
+                    paths, template
+                );
+
+                let organizer = Organizer::new(&settings.pictures_base_dir, template);
+                self.organizer = Some(organizer.clone());
+                self.paths = paths.clone();
+                self.transfer = settings.import_transfer;
+                self.is_busy = true;
+                self.description = fl!("import-dialog-planning");
+
+                // Reading metadata from every file on a memory card is slow.
+                std::thread::spawn(move || {
+                    let sources = Organizer::find_files(&paths);
+                    let placements = organizer.plan(&sources);
+                    sender.input(ImportInput::Planned(placements));
+                });
+            }
+            ImportInput::Planned(placements) => {
+                self.is_busy = false;
+
+                // More files were dropped while planning, so preview them all together.
+                if !self.queued_paths.is_empty() {
+                    let mut paths = std::mem::take(&mut self.paths);
+                    paths.append(&mut self.queued_paths);
+                    sender.input(ImportInput::Preview(paths));
+                    return;
+                }
+
+                self.placements = placements;
+
+                if self.placements.is_empty() {
+                    self.organizer = None;
+                    self.description = fl!("import-dialog-no-files");
+                    return;
+                }
+
+                self.description = match self.transfer {
+                    Transfer::Copy => fl!("import-dialog-copy-preview", count = self.to_import()),
+                    Transfer::Move => fl!("import-dialog-move-preview", count = self.to_import()),
+                };
+
+                self.show_placements();
+            }
+            ImportInput::Import => {
+                let Some(ref organizer) = self.organizer else {
+                    return;
+                };
+
+                if self.is_busy {
+                    return;
+                }
+
+                self.is_busy = true;
+                self.description = fl!("import-dialog-importing", count = self.to_import());
+
+                let organizer = organizer.clone();
+                let placements = self.placements.clone();
+                let transfer = self.transfer;
+
+                std::thread::spawn(move || {
+                    let summary = organizer.execute(&placements, transfer);
+                    sender.input(ImportInput::Imported(summary));
+                });
+            }
+            ImportInput::Imported(summary) => {
+                self.is_busy = false;
+                self.organizer = None;
+                self.placements.clear();
+                self.placements_list.remove_all();
+
+                self.description = if summary.failed > 0 && summary.imported > 0 {
+                    fl!(
+                        "import-dialog-partial",
+                        imported = summary.imported,
+                        failed = summary.failed
+                    )
+                } else if summary.failed > 0 {
+                    fl!("import-dialog-failed", count = summary.failed)
+                } else {
+                    fl!("import-dialog-done", count = summary.imported)
+                };
+
+                if summary.imported > 0 {
+                    let _ = sender.output(ImportOutput::Imported);
+                }
+
+                // Files were dropped while importing, so preview them next.
+                if !self.queued_paths.is_empty() {
+                    let paths = std::mem::take(&mut self.queued_paths);
+                    sender.input(ImportInput::Preview(paths));
+                }
+            }
+        }
+    }
+}
+
+impl ImportDialog {
+    /// Count of planned files that aren't already in the library.
+    fn to_import(&self) -> usize {
+        self.placements.iter().filter(|p| !p.is_duplicate).count()
+    }
+
+    fn show_placements(&self) {
+        let library_base_dir = self.settings_state.read().pictures_base_dir.clone();
+
+        for placement in self.placements.iter().take(MAX_PREVIEW_ROWS) {
+            let destination = placement
+                .destination
+                .strip_prefix(&library_base_dir)
+                .unwrap_or(&placement.destination);
+
+            let subtitle = if placement.is_duplicate {
+                fl!("import-dialog-duplicate")
+            } else {
+                placement.source.to_string_lossy().to_string()
+            };
+
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&destination.to_string_lossy()))
+                .subtitle(glib::markup_escape_text(&subtitle))
+                .build();
+
+            if placement.is_duplicate {
+                row.add_css_class("dim-label");
+            }
+
+            self.placements_list.append(&row);
+        }
+
+        if self.placements.len() > MAX_PREVIEW_ROWS {
+            let row = adw::ActionRow::builder()
+                .title(fl!(
+                    "import-dialog-more",
+                    count = self.placements.len() - MAX_PREVIEW_ROWS
+                ))
+                .build();
+            self.placements_list.append(&row);
+        }
+    }
+}
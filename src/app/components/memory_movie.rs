@@ -0,0 +1,401 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use relm4::adw::{self, prelude::*};
+use relm4::gtk::{self, gio, glib};
+use relm4::prelude::*;
+use relm4::*;
+
+use crate::app::components::albums::album_filter::AlbumFilter;
+use crate::app::portal;
+use crate::app::LibraryAvailability;
+use crate::app::SettingsState;
+use crate::app::SharedState;
+use crate::config::APP_ID;
+use crate::fl;
+
+use fotema_core::memory_movie::selection;
+use fotema_core::memory_movie::MovieMaker;
+use fotema_core::network_share::Availability;
+use fotema_core::Visual;
+
+use chrono::Local;
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use tracing::{error, info};
+
+/// Most pictures to put in a movie. At four seconds a picture, a movie is at most two minutes.
+const MAX_SHOTS: usize = 30;
+
+/// Folder in pictures library for saving movies.
+const LIBRARY_FOLDER: &str = "Memories";
+
+/// Pictures to make a movie from.
+#[derive(Debug, Clone)]
+pub enum MemoryMovieSource {
+    /// Pictures in an album.
+    Album(AlbumFilter),
+
+    /// Pictures taken on today's date in previous years.
+    OnThisDay,
+}
+
+#[derive(Debug)]
+pub enum MemoryMovieInput {
+    /// Pick pictures and start rendering a movie.
+    Create(MemoryMovieSource),
+
+    /// Number of pictures rendered so far for a render.
+    Progress(u64, usize),
+
+    /// Movie for a render has been rendered to a file.
+    Rendered(u64, PathBuf),
+
+    /// Movie for a render could not be rendered.
+    Failed(u64, String),
+
+    /// Dialog has been closed, so stop rendering and discard any unsaved movie.
+    Stop,
+
+    /// Copy rendered movie into the pictures library.
+    SaveToLibrary,
+
+    /// Send rendered movie to another app.
+    Share,
+}
+
+#[derive(Debug)]
+pub enum MemoryMovieOutput {
+    /// Movie has been saved to the library, which should be scanned again.
+    Saved,
+}
+
+pub struct MemoryMovie {
+    state: SharedState,
+
+    settings_state: SettingsState,
+
+    library_availability: LibraryAvailability,
+
+    parent: adw::ApplicationWindow,
+
+    dialog: adw::Dialog,
+
+    // Stop flag for current rendering thread.
+    stop: Arc<AtomicBool>,
+
+    // Identifies current render. A stopped render can still send messages
+    // after a new one has started, so those messages are ignored.
+    render_id: u64,
+
+    is_rendering: bool,
+
+    // Count of pictures to render.
+    total: usize,
+
+    // Count of pictures rendered so far.
+    rendered: usize,
+
+    // Rendered movie. None until rendering has completed.
+    movie_path: Option<PathBuf>,
+
+    is_saved: bool,
+
+    description: String,
+}
+
+#[relm4::component(pub async)]
+impl SimpleAsyncComponent for MemoryMovie {
+    type Init = (
+        SharedState,
+        SettingsState,
+        LibraryAvailability,
+        adw::ApplicationWindow,
+    );
+    type Input = MemoryMovieInput;
+    type Output = MemoryMovieOutput;
+
+    view! {
+        adw::Dialog {
+            set_title: &fl!("memory-movie-dialog"),
+            set_content_width: 360,
+
+            connect_closed => MemoryMovieInput::Stop,
+
+            #[wrap(Some)]
+            set_child = &adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar,
+
+                #[wrap(Some)]
+                set_content = &adw::StatusPage {
+                    set_icon_name: Some("video-reel-symbolic"),
+
+                    #[watch]
+                    set_description: Some(&model.description),
+
+                    #[wrap(Some)]
+                    set_child = &gtk::Box {
+                        set_orientation: gtk::Orientation::Vertical,
+                        set_halign: gtk::Align::Center,
+                        set_spacing: 12,
+
+                        gtk::ProgressBar {
+                            #[watch]
+                            set_visible: model.is_rendering,
+
+                            #[watch]
+                            set_fraction: model.fraction(),
+                        },
+
+                        gtk::Button {
+                            set_label: &fl!("memory-movie-dialog", "save"),
+                            add_css_class: "pill",
+                            add_css_class: "suggested-action",
+
+                            #[watch]
+                            set_visible: model.movie_path.is_some() && !model.is_saved,
+
+                            connect_clicked => MemoryMovieInput::SaveToLibrary,
+                        },
+
+                        gtk::Button {
+                            set_label: &fl!("memory-movie-dialog", "share"),
+                            add_css_class: "pill",
+
+                            #[watch]
+                            set_visible: model.movie_path.is_some(),
+
+                            connect_clicked => MemoryMovieInput::Share,
+                        },
+                    },
+                },
+            },
+        }
+    }
+
+    async fn init(
+        (state, settings_state, library_availability, parent): Self::Init,
+        dialog: Self::Root,
+        sender: AsyncComponentSender<Self>,
+    ) -> AsyncComponentParts<Self> {
+        let model = Self {
+            state,
+            settings_state,
+            library_availability,
+            parent,
+            dialog: dialog.clone(),
+            stop: Arc::new(AtomicBool::new(false)),
+            render_id: 0,
+            is_rendering: false,
+            total: 0,
+            rendered: 0,
+            movie_path: None,
+            is_saved: false,
+            description: String::new(),
+        };
+
+        let widgets = view_output!();
+
+        AsyncComponentParts { model, widgets }
+    }
+
+    async fn update(&mut self, msg: Self::Input, sender: AsyncComponentSender<Self>) {
+        match msg {
+            MemoryMovieInput::Create(source) => {
+                self.dialog.present(Some(&self.parent));
+
+                if self.is_rendering {
+                    return;
+                }
+
+                let visuals: Vec<Arc<Visual>> = {
+                    let data = self.state.read();
+                    match source {
+                        MemoryMovieSource::Album(ref filter) => data
+                            .iter()
+                            .filter(|v| filter.clone().filter(v))
+                            .cloned()
+                            .collect(),
+                        MemoryMovieSource::OnThisDay => {
+                            selection::on_this_day(&data, Local::now().date_naive())
+                        }
+                    }
+                };
+
+                let picture_paths: Vec<PathBuf> = selection::best_shots(&visuals, MAX_SHOTS)
+                    .into_iter()
+                    .filter_map(|v| v.picture_path.clone())
+                    .collect();
+
+                self.discard_movie();
+
+                if picture_paths.is_empty() {
+                    self.description = fl!("memory-movie-no-pictures");
+                    return;
+                }
+
+                info!(
+                    "Creating memory movie of {} pictures from {:?}",
+                    picture_paths.len(),
+                    source
+                );
+
+                self.is_rendering = true;
+                self.total = picture_paths.len();
+                self.rendered = 0;
+                self.description = fl!("memory-movie-rendering", count = self.total);
+                self.render_id += 1;
+                self.stop = Arc::new(AtomicBool::new(false));
+
+                let render_id = self.render_id;
+                let name = Local::now().format("Memory %Y-%m-%d %H-%M-%S").to_string();
+                let cache_dir = glib::user_cache_dir().join(APP_ID);
+                let stop = self.stop.clone();
+
+                // Rendering takes minutes, so keep it off the UI thread.
+                std::thread::spawn(move || {
+                    let result = MovieMaker::build(&cache_dir).and_then(|maker| {
+                        maker.make(&name, &picture_paths, &stop, |count| {
+                            sender.input(MemoryMovieInput::Progress(render_id, count))
+                        })
+                    });
+
+                    match result {
+                        Ok(path) => sender.input(MemoryMovieInput::Rendered(render_id, path)),
+                        Err(e) => {
+                            sender.input(MemoryMovieInput::Failed(render_id, format!("{:?}", e)))
+                        }
+                    }
+                });
+            }
+            MemoryMovieInput::Progress(render_id, count) => {
+                if render_id == self.render_id {
+                    self.rendered = count;
+                }
+            }
+            MemoryMovieInput::Rendered(render_id, path) => {
+                if render_id != self.render_id {
+                    info!("Discarding stopped memory movie: {:?}", path);
+                    let _ = std::fs::remove_file(&path);
+                    return;
+                }
+                info!("Rendered memory movie: {:?}", path);
+                self.is_rendering = false;
+                self.movie_path = Some(path);
+                self.description = fl!("memory-movie-rendered");
+            }
+            MemoryMovieInput::Failed(render_id, e) => {
+                if render_id != self.render_id {
+                    info!("Memory movie stopped");
+                    return;
+                }
+                error!("Failed rendering memory movie: {}", e);
+                self.is_rendering = false;
+                self.description = fl!("memory-movie-failed");
+            }
+            MemoryMovieInput::Stop => {
+                if self.is_rendering {
+                    info!("Stopping memory movie");
+                    self.stop.store(true, Ordering::Relaxed);
+                }
+
+                // Forget the stopped render straight away, so that the dialog can
+                // start a new one without waiting for the rendering thread to notice.
+                self.render_id += 1;
+                self.is_rendering = false;
+                self.description.clear();
+                self.discard_movie();
+            }
+            MemoryMovieInput::SaveToLibrary => {
+                if self.is_saved {
+                    return;
+                }
+
+                let Some(movie_path) = self.movie_path.clone() else {
+                    return;
+                };
+                let Some(file_name) = movie_path.file_name() else {
+                    return;
+                };
+
+                // Saving to an unmounted share would put the movie on the local disk
+                // under the mount point, where it would be hidden when the share returns.
+                if *self.library_availability.read() == Availability::Offline {
+                    self.description = fl!("memory-movie-offline");
+                    return;
+                }
+
+                let folder = self
+                    .settings_state
+                    .read()
+                    .pictures_base_dir
+                    .join(LIBRARY_FOLDER);
+
+                let saved_path = folder.join(file_name);
+
+                // Move rather than copy so the rendered movie doesn't linger in the cache.
+                // Renaming fails if the library is on another file system, and copying
+                // to a network share is slow, so don't block the main thread.
+                let result = {
+                    let folder = folder.clone();
+                    let saved_path = saved_path.clone();
+                    gio::spawn_blocking(move || {
+                        std::fs::create_dir_all(&folder).and_then(|_| {
+                            std::fs::rename(&movie_path, &saved_path).or_else(|_| {
+                                std::fs::copy(&movie_path, &saved_path)?;
+                                std::fs::remove_file(&movie_path)
+                            })
+                        })
+                    })
+                    .await
+                    .unwrap_or_else(|_| Err(std::io::Error::other("Saving thread panicked")))
+                };
+
+                if let Err(e) = result {
+                    error!("Failed saving memory movie to {:?}: {}", folder, e);
+                    self.description = fl!("memory-movie-failed");
+                    return;
+                }
+
+                info!("Saved memory movie to {:?}", saved_path);
+                self.movie_path = Some(saved_path);
+                self.is_saved = true;
+                self.description = fl!("memory-movie-saved", folder = LIBRARY_FOLDER);
+                let _ = sender.output(MemoryMovieOutput::Saved);
+            }
+            MemoryMovieInput::Share => {
+                if let Some(ref movie_path) = self.movie_path {
+                    portal::share_file(&self.parent, movie_path).await;
+                }
+            }
+        }
+    }
+}
+
+impl MemoryMovie {
+    /// Forget the rendered movie, deleting it from the cache if it hasn't been
+    /// saved to the library.
+    fn discard_movie(&mut self) {
+        if let Some(movie_path) = self.movie_path.take() {
+            if !self.is_saved {
+                info!("Deleting unsaved memory movie: {:?}", movie_path);
+                if let Err(e) = std::fs::remove_file(&movie_path) {
+                    error!("Failed deleting {:?}: {}", movie_path, e);
+                }
+            }
+        }
+        self.is_saved = false;
+    }
+
+    fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.rendered as f64 / self.total as f64
+        }
+    }
+}
@@ -4,9 +4,12 @@
 
 pub mod about;
 pub mod albums;
+pub mod import;
 pub mod library;
+pub mod memory_movie;
 pub mod onboard;
 pub mod preferences;
+pub mod problem_files;
 pub mod progress_monitor;
 pub mod progress_panel;
 pub mod viewer;
@@ -2,19 +2,14 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use ashpd::{desktop::file_chooser::OpenFileRequest, WindowIdentifier};
-
 use relm4::gtk;
 use relm4::gtk::prelude::*;
 use relm4::prelude::*;
 
+use crate::app::portal::{self, GrantedDirectory};
 use crate::fl;
 
-use regex::Regex;
-
-use tracing::{debug, error, info};
-
-use std::path::PathBuf;
+use tracing::info;
 
 #[derive(Debug)]
 pub enum OnboardInput {
@@ -25,7 +20,7 @@ pub enum OnboardInput {
 #[derive(Debug)]
 pub enum OnboardOutput {
     /// Onboarding process is complete
-    Done(PathBuf),
+    Done(GrantedDirectory),
 }
 
 pub struct Onboard {
@@ -84,50 +79,9 @@ impl SimpleAsyncComponent for Onboard {
         match msg {
             OnboardInput::ChooseDirectory => {
                 info!("Presenting directory chooser");
-                if let Some(root) = gtk::Widget::root(self.root.widget_ref()) {
-                    let identifier = WindowIdentifier::from_native(&root).await;
-                    let request = OpenFileRequest::default()
-                        .directory(true)
-                        .identifier(identifier)
-                        .modal(true) // can't be modal without identifier.
-                        .multiple(false);
-
-                    match request.send().await.and_then(|r| r.response()) {
-                        Ok(files) => {
-                            info!("Open: {:?}", files);
-                            let Some(dir) =
-                                files.uris().first().and_then(|uri| uri.to_file_path().ok())
-                            else {
-                                error!("No directory!");
-                                return;
-                            };
-                            info!("User has chosen picture library at: {:?}", dir);
-
-                            // Parse Document ID from file chooser path.
-                            let doc_id = dir
-                                .to_str()
-                                .and_then(|s| {
-                                    let re = Regex::new(r"^/run/user/[0-9]+/doc/([0-9a-fA-F]+)/")
-                                        .unwrap();
-                                    re.captures(s)
-                                })
-                                .and_then(|re_match| re_match.get(1))
-                                .map(|doc_id_match| doc_id_match.as_str());
-
-                            if let Some(doc_id) = doc_id {
-                                debug!("Document ID={:?}", doc_id);
-                                // TODO use XDG Documents API go get host path from doc_id
-                                //let proxy = Documents::new().await.unwrap();
-                                //let hp = proxy.host_paths(&[doc_id]).await.unwrap();
-                                //info!("Host paths={:?}", hp);
-                            }
-
-                            let _ = sender.output(OnboardOutput::Done(dir));
-                        }
-                        Err(err) => {
-                            error!("Failed to open a file: {err}");
-                        }
-                    }
+                if let Some(dir) = portal::choose_directory(&self.root).await {
+                    info!("User has chosen picture library at: {:?}", dir);
+                    let _ = sender.output(OnboardOutput::Done(dir));
                 }
             }
         }
@@ -2,14 +2,15 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use ashpd::{desktop::file_chooser::OpenFileRequest, WindowIdentifier};
-
 use relm4::adw::prelude::*;
 use relm4::gtk;
 use relm4::prelude::*;
 
-use tracing::{error, info};
+use fotema_core::import::{Template, Transfer};
+
+use tracing::{info, warn};
 
+use crate::app::portal;
 use crate::app::AlbumSort;
 use crate::app::FaceDetectionMode;
 use crate::app::{Settings, SettingsState};
@@ -19,6 +20,8 @@ pub struct PreferencesDialog {
     parent: adw::ApplicationWindow,
     dialog: adw::PreferencesDialog,
     album_sort: adw::ComboRow,
+    import_template: adw::EntryRow,
+    import_transfer: adw::ComboRow,
 
     settings_state: SettingsState,
 
@@ -32,6 +35,11 @@ impl PreferencesDialog {
     }
 
     pub fn picture_base_dir_name(&self) -> String {
+        // Prefer host path because Documents portal paths are meaningless to the user.
+        if let Some(ref host_path) = self.settings.pictures_base_dir_host {
+            return host_path.to_string_lossy().to_string();
+        }
+
         self.settings
             .pictures_base_dir
             .file_name()
@@ -56,6 +64,14 @@ pub enum PreferencesInput {
     Sort(AlbumSort),
 
     ChoosePicturesDir,
+
+    UpdateRunInBackground(bool),
+
+    UpdateOrganizeImports(bool),
+
+    UpdateImportTemplate(String),
+
+    UpdateImportTransfer(Transfer),
 }
 
 #[relm4::component(pub async)]
@@ -135,7 +151,60 @@ impl SimpleAsyncComponent for PreferencesDialog {
                             set_tooltip_text: Some(&fl!("prefs-library-section-pictures-dir", "tooltip")),
                             connect_clicked => PreferencesInput::ChoosePicturesDir,
                         }
-                    }
+                    },
+
+                    adw::SwitchRow {
+                        set_title: &fl!("prefs-library-section-run-in-background", "title"),
+                        set_subtitle: &fl!("prefs-library-section-run-in-background", "subtitle"),
+
+                        #[watch]
+                        set_active: model.settings.run_in_background,
+
+                        connect_active_notify[sender] => move |switch| {
+                            let _ = sender.input_sender().send(PreferencesInput::UpdateRunInBackground(switch.is_active()));
+                        },
+                    },
+                },
+
+                add = &adw::PreferencesGroup {
+                    set_title: &fl!("prefs-import-section", "title"),
+                    set_description: Some(&fl!("prefs-import-section", "description")),
+
+                    adw::SwitchRow {
+                        set_title: &fl!("prefs-import-section-organize", "title"),
+                        set_subtitle: &fl!("prefs-import-section-organize", "subtitle"),
+
+                        #[watch]
+                        set_active: model.settings.organize_imports,
+
+                        connect_active_notify[sender] => move |switch| {
+                            let _ = sender.input_sender().send(PreferencesInput::UpdateOrganizeImports(switch.is_active()));
+                        },
+                    },
+
+                    #[local_ref]
+                    import_template_row -> adw::EntryRow {
+                        set_title: &fl!("prefs-import-section-template", "title"),
+                        set_tooltip_text: Some(&fl!("prefs-import-section-template", "tooltip")),
+                        set_show_apply_button: true,
+
+                        #[watch]
+                        set_sensitive: model.settings.organize_imports,
+
+                        connect_apply[sender] => move |row| {
+                            let _ = sender.input_sender().send(PreferencesInput::UpdateImportTemplate(row.text().into()));
+                        },
+                    },
+
+                    #[local_ref]
+                    import_transfer_row -> adw::ComboRow {
+                        set_title: &fl!("prefs-import-section-transfer", "title"),
+
+                        connect_selected_item_notify[sender] => move |row| {
+                            let transfer = Transfer::from_repr(row.selected()).unwrap_or_default();
+                            let _ = sender.input_sender().send(PreferencesInput::UpdateImportTransfer(transfer));
+                        }
+                    },
                 },
             }
         }
@@ -161,12 +230,24 @@ impl SimpleAsyncComponent for PreferencesDialog {
         ]);
         album_sort_row.set_model(Some(&list));
 
+        let import_transfer_row = adw::ComboRow::new();
+        let list = gtk::StringList::new(&[
+            &fl!("prefs-import-section-transfer", "copy"),
+            &fl!("prefs-import-section-transfer", "move"),
+        ]);
+        import_transfer_row.set_model(Some(&list));
+
+        let import_template_row = adw::EntryRow::new();
+        import_template_row.set_text(&settings_state.read().import_template);
+
         let model = Self {
             settings_state: settings_state.clone(),
             parent,
             dialog: dialog.clone(),
             settings: settings_state.read().clone(),
             album_sort: album_sort_row.clone(),
+            import_template: import_template_row.clone(),
+            import_transfer: import_transfer_row.clone(),
         };
 
         let widgets = view_output!();
@@ -180,6 +261,12 @@ impl SimpleAsyncComponent for PreferencesDialog {
         match msg {
             PreferencesInput::Present => {
                 self.settings = self.settings_state.read().clone();
+
+                // Discard any unapplied edit from when the dialog was last open.
+                self.import_template
+                    .set_text(&self.settings.import_template);
+                self.import_template.remove_css_class("error");
+
                 self.dialog.present(Some(&self.parent));
             }
             PreferencesInput::SettingsChanged(settings) => {
@@ -192,6 +279,9 @@ impl SimpleAsyncComponent for PreferencesDialog {
                 };
 
                 self.album_sort.set_selected(index);
+
+                self.import_transfer
+                    .set_selected(self.settings.import_transfer as u32);
             }
             PreferencesInput::UpdateShowSelfies(show_selfies) => {
                 info!("Update show selfies: {}", show_selfies);
@@ -208,43 +298,50 @@ impl SimpleAsyncComponent for PreferencesDialog {
                 self.settings.album_sort = mode;
                 *self.settings_state.write() = self.settings.clone();
             }
+            PreferencesInput::UpdateRunInBackground(enable) => {
+                if self.settings.run_in_background == enable {
+                    return;
+                }
+                info!("Update run in background: {}", enable);
+                // The user might deny permission, so the setting comes from the portal.
+                self.settings.run_in_background =
+                    portal::request_background(&self.parent, enable).await;
+                *self.settings_state.write() = self.settings.clone();
+            }
+            PreferencesInput::UpdateOrganizeImports(enable) => {
+                info!("Update organize imports: {}", enable);
+                self.settings.organize_imports = enable;
+                *self.settings_state.write() = self.settings.clone();
+            }
+            PreferencesInput::UpdateImportTemplate(template) => {
+                if let Err(e) = Template::parse(&template) {
+                    warn!("Invalid import template {:?}: {}", template, e);
+                    self.import_template.add_css_class("error");
+                    return;
+                }
+                info!("Update import template: {}", template);
+                self.import_template.remove_css_class("error");
+                self.settings.import_template = template.trim().to_string();
+                *self.settings_state.write() = self.settings.clone();
+            }
+            PreferencesInput::UpdateImportTransfer(transfer) => {
+                info!("Update import transfer: {:?}", transfer);
+                self.settings.import_transfer = transfer;
+                *self.settings_state.write() = self.settings.clone();
+            }
             PreferencesInput::ChoosePicturesDir => {
                 info!("Presenting select pictures directory file chooser");
-                if let Some(root) = gtk::Widget::root(self.parent.widget_ref()) {
-                    let identifier = WindowIdentifier::from_native(&root).await;
-                    let request = OpenFileRequest::default()
-                        .directory(true)
-                        .identifier(identifier)
-                        .modal(true) // can't be modal without identifier.
-                        .multiple(true);
-
-                    match request.send().await.and_then(|r| r.response()) {
-                        Ok(files) => {
-                            info!("Open: {:?}", files);
-                            if let Some(first) = files.uris().first() {
-                                info!("User has chosen picture library at: {:?}", first.path());
-                                if let Some(pictures_base_dir) =
-                                    files.uris().first().and_then(|uri| uri.to_file_path().ok())
-                                {
-                                    info!(
-                                        "User has chosen picture library at: {:?}",
-                                        pictures_base_dir
-                                    );
-                                    if self.settings.pictures_base_dir != pictures_base_dir {
-                                        info!(
-                                            "New pictures base director is: {:?}",
-                                            pictures_base_dir
-                                        );
-                                        self.settings.pictures_base_dir = pictures_base_dir;
-                                        *self.settings_state.write() = self.settings.clone();
-                                    }
-                                }
-                            }
-                        }
-                        Err(err) => {
-                            error!("Failed to open a file: {err}");
-                        }
-                    }
+                let Some(dir) = portal::choose_directory(&self.parent).await else {
+                    return;
+                };
+
+                info!("User has chosen picture library at: {:?}", dir);
+                if self.settings.pictures_base_dir != dir.path {
+                    info!("New pictures base director is: {:?}", dir.path);
+                    self.settings.pictures_base_dir = dir.path;
+                    self.settings.pictures_base_dir_doc_id = dir.doc_id;
+                    self.settings.pictures_base_dir_host = dir.host_path;
+                    *self.settings_state.write() = self.settings.clone();
                 }
             }
         }
@@ -0,0 +1,431 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use relm4::adw::{self, prelude::*};
+use relm4::gtk::{self, gio};
+use relm4::prelude::*;
+use relm4::*;
+
+use crate::app::ActiveView;
+use crate::app::SettingsState;
+use crate::app::ViewName;
+use crate::fl;
+
+use fotema_core::database;
+use fotema_core::quarantine;
+use fotema_core::quarantine::QuarantinedFile;
+
+use std::sync::{Arc, Mutex};
+
+use tracing::{error, info};
+
+#[derive(Debug)]
+pub enum ProblemFilesInput {
+    /// Problem files view is visible.
+    Activate,
+
+    /// Reload quarantined files from database.
+    Refresh,
+
+    /// Retry processing a file.
+    Retry(QuarantinedFile),
+
+    /// Retry processing all files.
+    RetryAll,
+
+    /// Remove a file from the library, leaving the file where it is.
+    Remove(QuarantinedFile),
+
+    /// Ask user to confirm moving a file to the trash.
+    TrashDialog(QuarantinedFile),
+
+    /// Move a file to the trash and remove it from the library.
+    Trash(QuarantinedFile),
+
+    /// Open folder containing file.
+    OpenFolder(QuarantinedFile),
+}
+
+#[derive(Debug)]
+pub enum ProblemFilesOutput {
+    /// One or more files have been released from quarantine and
+    /// should be processed again.
+    Retried,
+}
+
+pub struct ProblemFiles {
+    con: Arc<Mutex<database::Connection>>,
+
+    settings_state: SettingsState,
+
+    active_view: ActiveView,
+
+    /// Quarantined files, in the same order as files_list.
+    files: Vec<QuarantinedFile>,
+
+    files_list: gtk::ListBox,
+
+    files_page: gtk::ScrolledWindow,
+
+    status: adw::StatusPage,
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for ProblemFiles {
+    type Init = (Arc<Mutex<database::Connection>>, SettingsState, ActiveView);
+    type Input = ProblemFilesInput;
+    type Output = ProblemFilesOutput;
+
+    view! {
+        gtk::Box {
+            set_orientation: gtk::Orientation::Vertical,
+
+            #[local_ref]
+            files_page -> gtk::ScrolledWindow {
+                set_vexpand: true,
+
+                adw::Clamp {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_maximum_size: 800,
+
+                    gtk::Box {
+                        set_orientation: gtk::Orientation::Vertical,
+                        set_margin_all: 12,
+                        set_spacing: 12,
+
+                        gtk::Label {
+                            set_label: &fl!("problem-files-page", "description"),
+                            set_wrap: true,
+                            set_xalign: 0.0,
+                            add_css_class: "dim-label",
+                        },
+
+                        gtk::Button {
+                            set_halign: gtk::Align::End,
+                            set_label: &fl!("problem-files-page", "retry-all"),
+                            add_css_class: "pill",
+                            connect_clicked => ProblemFilesInput::RetryAll,
+                        },
+
+                        #[local_ref]
+                        files_list -> gtk::ListBox,
+                    }
+                }
+            },
+
+            #[local_ref]
+            status -> adw::StatusPage {
+                set_valign: gtk::Align::Start,
+                set_vexpand: true,
+                set_visible: false,
+
+                set_icon_name: Some("emblem-ok-symbolic"),
+                set_title: &fl!("problem-files-page-status-none", "title"),
+                set_description: Some(&fl!("problem-files-page-status-none", "description")),
+            },
+        }
+    }
+
+    fn init(
+        (con, settings_state, active_view): Self::Init,
+        _root: Self::Root,
+        _sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let files_list = gtk::ListBox::builder()
+            .css_classes(["boxed-list"])
+            .selection_mode(gtk::SelectionMode::None)
+            .build();
+
+        let files_page = gtk::ScrolledWindow::new();
+
+        let status = adw::StatusPage::new();
+
+        let model = ProblemFiles {
+            con,
+            settings_state,
+            active_view,
+            files: vec![],
+            files_list: files_list.clone(),
+            files_page: files_page.clone(),
+            status: status.clone(),
+        };
+
+        let widgets = view_output!();
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
+        match msg {
+            ProblemFilesInput::Activate => {
+                *self.active_view.write() = ViewName::ProblemFiles;
+                self.refresh(&sender);
+            }
+            ProblemFilesInput::Refresh => {
+                if *self.active_view.read() == ViewName::ProblemFiles {
+                    self.refresh(&sender);
+                }
+            }
+            ProblemFilesInput::Retry(file) => {
+                // Files might have been reloaded since the message was sent.
+                let Some(file) = self.find(&file) else {
+                    return;
+                };
+                info!("Retrying {:?}", file.path);
+                if let Err(e) = self.retry(file) {
+                    error!("Failed retrying {:?}: {}", file.path, e);
+                }
+                self.refresh(&sender);
+                let _ = sender.output(ProblemFilesOutput::Retried);
+            }
+            ProblemFilesInput::RetryAll => {
+                info!("Retrying all {} problem files", self.files.len());
+                for file in self.files.iter() {
+                    if let Err(e) = self.retry(file) {
+                        error!("Failed retrying {:?}: {}", file.path, e);
+                    }
+                }
+                self.refresh(&sender);
+                let _ = sender.output(ProblemFilesOutput::Retried);
+            }
+            ProblemFilesInput::Remove(file) => {
+                // Files might have been reloaded since the message was sent.
+                let Some(file) = self.find(&file) else {
+                    return;
+                };
+                info!("Removing {:?}", file.path);
+                if let Err(e) = self.remove(file) {
+                    error!("Failed removing {:?}: {}", file.path, e);
+                }
+                self.refresh(&sender);
+            }
+            ProblemFilesInput::TrashDialog(file) => {
+                // Files might have been reloaded since the message was sent.
+                let Some(file) = self.find(&file) else {
+                    return;
+                };
+
+                let file_name = file
+                    .path
+                    .file_name()
+                    .map(|x| x.to_string_lossy().to_string())
+                    .unwrap_or_default();
+
+                let dialog = adw::AlertDialog::builder()
+                    .heading(fl!("problem-files-trash-dialog", "heading"))
+                    .body(fl!(
+                        "problem-files-trash-dialog-body",
+                        file_name = file_name
+                    ))
+                    .build();
+
+                dialog.add_response(
+                    "cancel",
+                    &fl!("problem-files-trash-dialog", "cancel-button"),
+                );
+                dialog.set_default_response(Some("cancel"));
+                dialog.set_close_response("cancel");
+
+                dialog.add_response("trash", &fl!("problem-files-trash-dialog", "trash-button"));
+                dialog.set_response_appearance("trash", adw::ResponseAppearance::Destructive);
+
+                {
+                    let sender = sender.clone();
+                    let file = file.clone();
+                    dialog.connect_response(None, move |_, response| {
+                        if response == "trash" {
+                            sender.input(ProblemFilesInput::Trash(file.clone()));
+                        }
+                    });
+                }
+
+                if let Some(root) = self.files_list.root() {
+                    dialog.present(Some(&root));
+                } else {
+                    error!("Couldn't get root widget!");
+                }
+            }
+            ProblemFilesInput::Trash(file) => {
+                // Files might have been reloaded since the message was sent.
+                let Some(file) = self.find(&file) else {
+                    return;
+                };
+                info!("Moving {:?} to trash", file.path);
+                if let Err(e) = self.trash(file) {
+                    error!("Failed moving {:?} to trash: {}", file.path, e);
+                }
+                self.refresh(&sender);
+            }
+            ProblemFilesInput::OpenFolder(file) => {
+                // Files might have been reloaded since the message was sent.
+                let Some(file) = self.find(&file) else {
+                    return;
+                };
+                let file = gio::File::for_path(&file.path);
+                let launcher = gtk::FileLauncher::new(Some(&file));
+                launcher.open_containing_folder(
+                    None::<&adw::ApplicationWindow>,
+                    None::<&gio::Cancellable>,
+                    |_| (),
+                );
+            }
+        }
+    }
+}
+
+impl ProblemFiles {
+    fn repo(&self) -> anyhow::Result<quarantine::Repository> {
+        let pictures_base_dir = self.settings_state.read().pictures_base_dir.clone();
+        quarantine::Repository::open(&pictures_base_dir, self.con.clone())
+    }
+
+    /// Current quarantine entry for the same picture or video as `file`.
+    fn find(&self, file: &QuarantinedFile) -> Option<&QuarantinedFile> {
+        self.files
+            .iter()
+            .find(|f| f.picture_id == file.picture_id && f.video_id == file.video_id)
+    }
+
+    fn retry(&self, file: &QuarantinedFile) -> anyhow::Result<()> {
+        let mut repo = self.repo()?;
+        if let Some(picture_id) = file.picture_id {
+            repo.retry_picture(picture_id)?;
+        } else if let Some(video_id) = file.video_id {
+            repo.retry_video(video_id)?;
+        }
+        Ok(())
+    }
+
+    fn remove(&self, file: &QuarantinedFile) -> anyhow::Result<()> {
+        let mut repo = self.repo()?;
+        if let Some(picture_id) = file.picture_id {
+            repo.remove_picture(picture_id)?;
+        } else if let Some(video_id) = file.video_id {
+            repo.remove_video(video_id)?;
+        }
+        Ok(())
+    }
+
+    fn trash(&self, file: &QuarantinedFile) -> anyhow::Result<()> {
+        // Move to trash instead of deleting so the user can change their mind.
+        if file.path.exists() {
+            gio::File::for_path(&file.path).trash(None::<&gio::Cancellable>)?;
+        }
+
+        let mut repo = self.repo()?;
+        if let Some(picture_id) = file.picture_id {
+            repo.delete_picture(picture_id)?;
+        } else if let Some(video_id) = file.video_id {
+            repo.delete_video(video_id)?;
+        }
+        Ok(())
+    }
+
+    fn refresh(&mut self, sender: &ComponentSender<Self>) {
+        self.files_list.remove_all();
+
+        self.files = match self.repo().and_then(|repo| repo.all()) {
+            Ok(files) => files,
+            Err(e) => {
+                error!("Failed loading problem files: {}", e);
+                vec![]
+            }
+        };
+
+        for file in self.files.iter() {
+            let file_name = file
+                .path
+                .file_name()
+                .map(|x| x.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            let stage = match file.stage {
+                quarantine::Stage::Metadata => fl!("problem-files-stage", "metadata"),
+                quarantine::Stage::Thumbnail => fl!("problem-files-stage", "thumbnail"),
+                quarantine::Stage::MotionPhoto => fl!("problem-files-stage", "motion-photo"),
+            };
+
+            let row = adw::ActionRow::builder()
+                .title(gtk::glib::markup_escape_text(&file_name))
+                .subtitle(gtk::glib::markup_escape_text(&format!(
+                    "{}: {}",
+                    stage, file.error
+                )))
+                .subtitle_lines(3)
+                .tooltip_text(file.path.to_string_lossy())
+                .build();
+
+            let open_button = gtk::Button::builder()
+                .valign(gtk::Align::Center)
+                .icon_name("folder-open-symbolic")
+                .tooltip_text(fl!("problem-files-page", "open-folder"))
+                .css_classes(["flat"])
+                .build();
+
+            let retry_button = gtk::Button::builder()
+                .valign(gtk::Align::Center)
+                .icon_name("view-refresh-symbolic")
+                .tooltip_text(fl!("problem-files-page", "retry"))
+                .css_classes(["flat"])
+                .build();
+
+            let remove_button = gtk::Button::builder()
+                .valign(gtk::Align::Center)
+                .icon_name("list-remove-symbolic")
+                .tooltip_text(fl!("problem-files-page", "remove"))
+                .css_classes(["flat"])
+                .build();
+
+            let trash_button = gtk::Button::builder()
+                .valign(gtk::Align::Center)
+                .icon_name("user-trash-symbolic")
+                .tooltip_text(fl!("problem-files-page", "trash"))
+                .css_classes(["flat"])
+                .build();
+
+            {
+                let sender = sender.clone();
+                let file = file.clone();
+                open_button.connect_clicked(move |_| {
+                    sender.input(ProblemFilesInput::OpenFolder(file.clone()))
+                });
+            }
+
+            {
+                let sender = sender.clone();
+                let file = file.clone();
+                retry_button
+                    .connect_clicked(move |_| sender.input(ProblemFilesInput::Retry(file.clone())));
+            }
+
+            {
+                let sender = sender.clone();
+                let file = file.clone();
+                remove_button.connect_clicked(move |_| {
+                    sender.input(ProblemFilesInput::Remove(file.clone()))
+                });
+            }
+
+            {
+                let sender = sender.clone();
+                let file = file.clone();
+                trash_button.connect_clicked(move |_| {
+                    sender.input(ProblemFilesInput::TrashDialog(file.clone()))
+                });
+            }
+
+            row.add_suffix(&open_button);
+            row.add_suffix(&retry_button);
+            row.add_suffix(&remove_button);
+            row.add_suffix(&trash_button);
+
+            self.files_list.append(&row);
+        }
+
+        info!("Showing {} problem files", self.files.len());
+
+        self.status.set_visible(self.files.is_empty());
+        self.files_page.set_visible(!self.files.is_empty());
+    }
+}
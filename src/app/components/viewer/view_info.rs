@@ -3,7 +3,9 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use super::face_thumbnails::{FaceThumbnails, FaceThumbnailsInput};
+use fotema_core::caption::{self, Caption};
 use fotema_core::people;
+use fotema_core::PictureId;
 /// Properties view for a photo.
 ///Inspired by how Loupe displays its property view.
 use fotema_core::VisualId;
@@ -41,12 +43,29 @@ pub enum ViewInfoInput {
 
     /// Refresh faces
     RefreshFaces,
+
+    /// Save edited title, caption, and description
+    SaveCaption,
 }
 
 pub struct ViewInfo {
     state: SharedState,
 
+    caption_repo: caption::Repository,
+
     path: Option<PathBuf>,
+
+    // Picture being viewed. None if viewing a video.
+    picture_id: Option<PictureId>,
+
+    // Caption as last loaded or saved, so unchanged captions aren't saved again.
+    saved_caption: Caption,
+
+    caption_details: adw::PreferencesGroup,
+    caption_title: adw::EntryRow,
+    caption_caption: adw::EntryRow,
+    caption_description: adw::EntryRow,
+
     folder: adw::ActionRow,
     file_name: adw::ActionRow,
 
@@ -78,7 +97,7 @@ pub struct ViewInfo {
 
 #[relm4::component(pub)]
 impl SimpleComponent for ViewInfo {
-    type Init = (SharedState, people::Repository);
+    type Init = (SharedState, people::Repository, caption::Repository);
     type Input = ViewInfoInput;
     type Output = ();
 
@@ -92,6 +111,30 @@ impl SimpleComponent for ViewInfo {
                 set_margin_all: 12,
                 set_spacing: 12,
 
+                #[local_ref]
+                caption_details -> adw::PreferencesGroup {
+                    #[local_ref]
+                    caption_title -> adw::EntryRow {
+                        set_title: &fl!("infobar-caption", "title"),
+                        set_show_apply_button: true,
+                        connect_apply => ViewInfoInput::SaveCaption,
+                    },
+
+                    #[local_ref]
+                    caption_caption -> adw::EntryRow {
+                        set_title: &fl!("infobar-caption", "caption"),
+                        set_show_apply_button: true,
+                        connect_apply => ViewInfoInput::SaveCaption,
+                    },
+
+                    #[local_ref]
+                    caption_description -> adw::EntryRow {
+                        set_title: &fl!("infobar-caption", "description"),
+                        set_show_apply_button: true,
+                        connect_apply => ViewInfoInput::SaveCaption,
+                    },
+                },
+
                 adw::PreferencesGroup {
                     #[local_ref]
                     folder -> adw::ActionRow {
@@ -286,10 +329,15 @@ impl SimpleComponent for ViewInfo {
     }
 
     fn init(
-        (state, people_repo): Self::Init,
+        (state, people_repo, caption_repo): Self::Init,
         _root: Self::Root,
-        _sender: ComponentSender<Self>,
+        sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
+        let caption_details = adw::PreferencesGroup::new();
+        let caption_title = adw::EntryRow::new();
+        let caption_caption = adw::EntryRow::new();
+        let caption_description = adw::EntryRow::new();
+
         let folder = adw::ActionRow::new();
         let file_name = adw::ActionRow::new();
 
@@ -319,6 +367,14 @@ impl SimpleComponent for ViewInfo {
 
         let model = ViewInfo {
             state,
+            caption_repo,
+
+            picture_id: None,
+            saved_caption: Caption::default(),
+            caption_details: caption_details.clone(),
+            caption_title: caption_title.clone(),
+            caption_caption: caption_caption.clone(),
+            caption_description: caption_description.clone(),
 
             folder: folder.clone(),
             file_name: file_name.clone(),
@@ -351,6 +407,14 @@ impl SimpleComponent for ViewInfo {
 
         let widgets = view_output!();
 
+        // Save edits when moving away from a caption row without pressing apply.
+        for row in [&caption_title, &caption_caption, &caption_description] {
+            let focus = gtk::EventControllerFocus::new();
+            let sender = sender.clone();
+            focus.connect_leave(move |_| sender.input(ViewInfoInput::SaveCaption));
+            row.add_controller(focus);
+        }
+
         ComponentParts { model, widgets }
     }
 
@@ -384,6 +448,7 @@ impl SimpleComponent for ViewInfo {
                 self.exif_details.set_visible(false);
 
                 let _ = self.update_file_details(vis.clone());
+                self.update_caption_details(vis.picture_id);
             }
             ViewInfoInput::Photo(ref visual_id, ref image_info) => {
                 let result = {
@@ -399,6 +464,7 @@ impl SimpleComponent for ViewInfo {
                 self.video_details.set_visible(false);
 
                 let _ = self.update_file_details(vis.clone());
+                self.update_caption_details(vis.picture_id);
 
                 if let Some(picture_id) = vis.picture_id {
                     let _ = self.update_photo_details(vis.clone(), image_info);
@@ -425,6 +491,7 @@ impl SimpleComponent for ViewInfo {
                 self.exif_details.set_visible(false);
 
                 let _ = self.update_file_details(vis.clone());
+                self.update_caption_details(None);
 
                 if vis.video_id.is_some() {
                     let _ = self.update_video_details(vis.clone());
@@ -433,6 +500,9 @@ impl SimpleComponent for ViewInfo {
             ViewInfoInput::RefreshFaces => {
                 self.face_thumbnails.emit(FaceThumbnailsInput::Refresh);
             }
+            ViewInfoInput::SaveCaption => {
+                self.save_caption();
+            }
         }
     }
 }
@@ -441,6 +511,59 @@ impl SimpleComponent for ViewInfo {
 const FALLBACK: &str = "–";
 
 impl ViewInfo {
+    /// Save edited caption of picture being viewed, if it has changed.
+    fn save_caption(&mut self) {
+        let Some(picture_id) = self.picture_id else {
+            return;
+        };
+
+        let caption = Caption::new(
+            &self.caption_title.text(),
+            &self.caption_caption.text(),
+            &self.caption_description.text(),
+        );
+
+        if caption == self.saved_caption {
+            return;
+        }
+
+        event!(Level::INFO, "Saving caption for {}", picture_id);
+        if let Err(e) = self.caption_repo.set(picture_id, &caption) {
+            event!(Level::ERROR, "Failed saving caption: {}", e);
+            return;
+        }
+
+        self.saved_caption = caption;
+    }
+
+    /// Show editable caption for a picture. Videos don't have captions.
+    fn update_caption_details(&mut self, picture_id: Option<PictureId>) {
+        // Don't lose edits to the previous picture's caption.
+        self.save_caption();
+
+        self.picture_id = picture_id;
+
+        let Some(picture_id) = picture_id else {
+            self.caption_details.set_visible(false);
+            return;
+        };
+
+        let caption = self.caption_repo.get(picture_id).unwrap_or_else(|e| {
+            event!(Level::ERROR, "Failed loading caption: {}", e);
+            Caption::default()
+        });
+
+        self.caption_title
+            .set_text(caption.title.as_deref().unwrap_or_default());
+        self.caption_caption
+            .set_text(caption.caption.as_deref().unwrap_or_default());
+        self.caption_description
+            .set_text(caption.description.as_deref().unwrap_or_default());
+        self.caption_details.set_visible(true);
+
+        self.saved_caption = caption;
+    }
+
     fn update_file_details(&mut self, vis: Arc<fotema_core::visual::Visual>) -> Result<(), String> {
         let Some(ref path) = vis.path() else {
             return Err("No picture or video path".to_string());
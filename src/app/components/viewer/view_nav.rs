@@ -18,9 +18,11 @@ use crate::app::components::albums::album_sort::AlbumSort;
 
 use crate::adaptive;
 use crate::app::components::progress_monitor::ProgressMonitor;
+use crate::app::LibraryAvailability;
 use crate::app::SharedState;
 use crate::fl;
 
+use fotema_core::caption;
 use fotema_core::people;
 use fotema_core::PictureId;
 use fotema_core::Visual;
@@ -161,6 +163,8 @@ impl SimpleAsyncComponent for ViewNav {
         Arc<Reducer<ProgressMonitor>>,
         Arc<adaptive::LayoutState>,
         people::Repository,
+        caption::Repository,
+        LibraryAvailability,
     );
     type Input = ViewNavInput;
     type Output = ViewNavOutput;
@@ -221,7 +225,14 @@ impl SimpleAsyncComponent for ViewNav {
     }
 
     async fn init(
-        (state, transcode_progress_monitor, layout_state, people_repo): Self::Init,
+        (
+            state,
+            transcode_progress_monitor,
+            layout_state,
+            people_repo,
+            caption_repo,
+            library_availability,
+        ): Self::Init,
         root: Self::Root,
         sender: AsyncComponentSender<Self>,
     ) -> AsyncComponentParts<Self> {
@@ -334,7 +345,10 @@ impl SimpleAsyncComponent for ViewNav {
 
         carousel_pages.push(
             ViewOne::builder()
-                .launch(transcode_progress_monitor.clone())
+                .launch((
+                    transcode_progress_monitor.clone(),
+                    library_availability.clone(),
+                ))
                 .forward(sender.input_sender(), |msg| match msg {
                     ViewOneOutput::TranscodeAll => ViewNavInput::TranscodeAll,
                     ViewOneOutput::PhotoShown(id, info) => ViewNavInput::ShowPhotoInfo(id, info),
@@ -346,7 +360,10 @@ impl SimpleAsyncComponent for ViewNav {
 
         carousel_pages.push(
             ViewOne::builder()
-                .launch(transcode_progress_monitor.clone())
+                .launch((
+                    transcode_progress_monitor.clone(),
+                    library_availability.clone(),
+                ))
                 .forward(sender.input_sender(), |msg| match msg {
                     ViewOneOutput::TranscodeAll => ViewNavInput::TranscodeAll,
                     ViewOneOutput::PhotoShown(id, info) => ViewNavInput::ShowPhotoInfo(id, info),
@@ -358,7 +375,10 @@ impl SimpleAsyncComponent for ViewNav {
 
         carousel_pages.push(
             ViewOne::builder()
-                .launch(transcode_progress_monitor.clone())
+                .launch((
+                    transcode_progress_monitor.clone(),
+                    library_availability.clone(),
+                ))
                 .forward(sender.input_sender(), |msg| match msg {
                     ViewOneOutput::TranscodeAll => ViewNavInput::TranscodeAll,
                     ViewOneOutput::PhotoShown(id, info) => ViewNavInput::ShowPhotoInfo(id, info),
@@ -369,7 +389,7 @@ impl SimpleAsyncComponent for ViewNav {
         );
 
         let view_info = ViewInfo::builder()
-            .launch((state.clone(), people_repo.clone()))
+            .launch((state.clone(), people_repo.clone(), caption_repo))
             .detach();
 
         layout_state.subscribe(sender.input_sender(), |layout| ViewNavInput::Adapt(*layout));
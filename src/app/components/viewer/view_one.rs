@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use chrono::TimeDelta;
+use fotema_core::network_share::Availability;
 use fotema_core::visual::model::PictureOrientation;
 use fotema_core::Visual;
 use fotema_core::VisualId;
@@ -17,6 +18,7 @@ use strum::IntoEnumIterator;
 
 use crate::app::components::progress_monitor::ProgressMonitor;
 use crate::app::components::progress_panel::ProgressPanel;
+use crate::app::LibraryAvailability;
 use crate::fl;
 
 use std::path::PathBuf;
@@ -34,6 +36,9 @@ pub enum Viewing {
     Video,
     Transcode,
     Error,
+
+    /// Library is offline, so showing the cached thumbnail.
+    Offline,
     None,
 }
 
@@ -140,11 +145,13 @@ pub struct ViewOne {
     video_timestamp: String,
 
     transcode_progress: Controller<ProgressPanel>,
+
+    library_availability: LibraryAvailability,
 }
 
 #[relm4::component(pub async)]
 impl SimpleAsyncComponent for ViewOne {
-    type Init = Arc<Reducer<ProgressMonitor>>;
+    type Init = (Arc<Reducer<ProgressMonitor>>, LibraryAvailability);
     type Input = ViewOneInput;
     type Output = ViewOneOutput;
 
@@ -154,6 +161,27 @@ impl SimpleAsyncComponent for ViewOne {
             set_vexpand: true,
             set_hexpand: true,
 
+            add_overlay = &gtk::Box {
+                set_halign: gtk::Align::Center,
+                set_valign: gtk::Align::Start,
+                set_margin_top: 18,
+                set_spacing: 6,
+                add_css_class: "osd",
+                add_css_class: "library-offline-badge",
+                set_tooltip_text: Some(&fl!("viewer-offline", "tooltip")),
+
+                #[watch]
+                set_visible: model.viewing == Viewing::Offline,
+
+                gtk::Image {
+                    set_icon_name: Some("network-offline-symbolic"),
+                },
+
+                gtk::Label {
+                    set_label: &fl!("library-offline-badge"),
+                },
+            },
+
             // video_controls
             add_overlay = &gtk::Box {
                 set_orientation: gtk::Orientation::Vertical,
@@ -264,7 +292,7 @@ impl SimpleAsyncComponent for ViewOne {
                     set_halign: gtk::Align::Center,
 
                     #[watch]
-                    set_visible: model.viewing == Viewing::Photo || model.viewing == Viewing::MotionPhoto || model.viewing == Viewing::Video,
+                    set_visible: model.viewing == Viewing::Photo || model.viewing == Viewing::MotionPhoto || model.viewing == Viewing::Video || model.viewing == Viewing::Offline,
 
                     #[local_ref]
                     picture -> gtk::Picture {}
@@ -320,7 +348,7 @@ impl SimpleAsyncComponent for ViewOne {
     }
 
     async fn init(
-        transcode_progress_monitor: Self::Init,
+        (transcode_progress_monitor, library_availability): Self::Init,
         root: Self::Root,
         _sender: AsyncComponentSender<Self>,
     ) -> AsyncComponentParts<Self> {
@@ -343,6 +371,7 @@ impl SimpleAsyncComponent for ViewOne {
             is_skipping_allowed: false,
             video_timestamp: "".into(),
             transcode_progress,
+            library_availability,
         };
 
         let widgets = view_output!();
@@ -373,6 +402,22 @@ impl SimpleAsyncComponent for ViewOne {
                     return;
                 };
 
+                if *self.library_availability.read() == Availability::Offline {
+                    // File is on a network share that isn't mounted, so show the cached
+                    // thumbnail, if there is one. Don't check if the file exists, because
+                    // that can block until the share times out.
+                    // Thumbnails are already oriented, so no transformation needed.
+                    for orient in PictureOrientation::iter() {
+                        self.picture.remove_css_class(orient.as_ref());
+                    }
+                    self.video = None;
+                    self.image_info = None;
+                    self.picture.set_filename(visual.thumbnail_path.as_ref());
+                    self.visual_id = Some(visual.visual_id.clone());
+                    self.viewing = Viewing::Offline;
+                    return;
+                }
+
                 if !visual_path.exists() {
                     self.viewing = Viewing::Error;
                     self.broken = Broken::MissingInFileSystem(visual_path.clone());
@@ -525,7 +570,7 @@ impl SimpleAsyncComponent for ViewOne {
                     Viewing::Transcode => {
                         let _ = sender.output(ViewOneOutput::TranscodeShown(visual_id.clone()));
                     }
-                    Viewing::Error => {
+                    Viewing::Error | Viewing::Offline => {
                         let _ = sender.output(ViewOneOutput::ErrorShown(visual_id.clone()));
                     }
                     Viewing::None => {}
@@ -0,0 +1,236 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// Watch the pictures library for new, changed, and deleted files.
+//
+// GIO directory monitors aren't recursive, so every directory in the library
+// gets its own monitor. Directories created or moved into the library are
+// watched as they appear. Copying a batch of photos into the library produces
+// a burst of events, so changes are only reported once the library has been
+// quiet for a little while.
+
+use relm4::gtk::prelude::*;
+use relm4::gtk::{gio, glib};
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::{Rc, Weak};
+
+use tracing::{debug, error, info};
+
+/// How long the library must be quiet before changes are reported.
+const SETTLE_SECONDS: u32 = 10;
+
+struct Inner {
+    /// Library directory being watched.
+    base_dir: RefCell<Option<PathBuf>>,
+
+    /// Monitors for the library directory and every directory inside it.
+    monitors: RefCell<HashMap<PathBuf, gio::FileMonitor>>,
+
+    /// Timer for reporting changes once the library is quiet.
+    pending: RefCell<Option<glib::SourceId>>,
+
+    /// Incremented when watching stops so that directories found for an
+    /// earlier library are not watched.
+    generation: Cell<u64>,
+
+    on_change: Box<dyn Fn()>,
+}
+
+/// Watches the library directory tree and calls back when its contents have changed.
+#[derive(Clone)]
+pub struct LibraryWatcher {
+    inner: Rc<Inner>,
+}
+
+impl LibraryWatcher {
+    pub fn new(on_change: impl Fn() + 'static) -> Self {
+        Self {
+            inner: Rc::new(Inner {
+                base_dir: RefCell::new(None),
+                monitors: RefCell::new(HashMap::new()),
+                pending: RefCell::new(None),
+                generation: Cell::new(0),
+                on_change: Box::new(on_change),
+            }),
+        }
+    }
+
+    /// Watch a library directory and all directories inside it.
+    /// Does nothing if the directory is already being watched.
+    pub fn watch(&self, base_dir: &Path) {
+        if self.inner.base_dir.borrow().as_deref() == Some(base_dir) {
+            return;
+        }
+
+        self.unwatch();
+
+        info!("Watching library {:?} for changes", base_dir);
+        self.inner.base_dir.replace(Some(base_dir.to_path_buf()));
+        self.add_tree(base_dir.to_path_buf());
+    }
+
+    /// Stop watching the library.
+    pub fn unwatch(&self) {
+        if let Some(base_dir) = self.inner.base_dir.take() {
+            info!("No longer watching library {:?}", base_dir);
+        }
+
+        self.inner.generation.set(self.inner.generation.get() + 1);
+
+        for (_, monitor) in self.inner.monitors.borrow_mut().drain() {
+            monitor.cancel();
+        }
+
+        if let Some(source_id) = self.inner.pending.take() {
+            source_id.remove();
+        }
+    }
+
+    /// Watch a directory and all directories inside it.
+    fn add_tree(&self, dir: PathBuf) {
+        let generation = self.inner.generation.get();
+        let weak = Rc::downgrade(&self.inner);
+
+        glib::spawn_future_local(async move {
+            // Walking a big library, possibly on a network share, is slow.
+            let dirs = gio::spawn_blocking(move || find_dirs(&dir))
+                .await
+                .unwrap_or_default();
+
+            let Some(inner) = weak.upgrade() else {
+                return;
+            };
+
+            if inner.generation.get() != generation {
+                return;
+            }
+
+            let watcher = LibraryWatcher { inner };
+            for dir in dirs {
+                watcher.add(dir);
+            }
+        });
+    }
+
+    fn add(&self, dir: PathBuf) {
+        if self.inner.monitors.borrow().contains_key(&dir) {
+            return;
+        }
+
+        let file = gio::File::for_path(&dir);
+        let monitor = match file
+            .monitor_directory(gio::FileMonitorFlags::WATCH_MOVES, gio::Cancellable::NONE)
+        {
+            Ok(monitor) => monitor,
+            Err(e) => {
+                error!("Failed watching {:?}: {}", dir, e);
+                return;
+            }
+        };
+
+        let weak: Weak<Inner> = Rc::downgrade(&self.inner);
+        monitor.connect_changed(move |_, file, other_file, event| {
+            if let Some(inner) = weak.upgrade() {
+                let watcher = LibraryWatcher { inner };
+                watcher.on_event(file, other_file, event);
+            }
+        });
+
+        debug!("Watching {:?}", dir);
+        self.inner.monitors.borrow_mut().insert(dir, monitor);
+    }
+
+    /// Stop watching a directory and all directories inside it.
+    fn remove_tree(&self, dir: &Path) {
+        self.inner.monitors.borrow_mut().retain(|path, monitor| {
+            let is_removed = path.starts_with(dir);
+            if is_removed {
+                monitor.cancel();
+            }
+            !is_removed
+        });
+    }
+
+    fn on_event(
+        &self,
+        file: &gio::File,
+        other_file: Option<&gio::File>,
+        event: gio::FileMonitorEvent,
+    ) {
+        match event {
+            gio::FileMonitorEvent::Created | gio::FileMonitorEvent::MovedIn => {
+                // Might be a new directory that should be watched too.
+                if let Some(path) = file.path() {
+                    self.add_tree(path);
+                }
+            }
+            gio::FileMonitorEvent::Deleted | gio::FileMonitorEvent::MovedOut => {
+                if let Some(path) = file.path() {
+                    self.remove_tree(&path);
+                }
+            }
+            gio::FileMonitorEvent::Renamed => {
+                if let Some(path) = file.path() {
+                    self.remove_tree(&path);
+                }
+                if let Some(path) = other_file.and_then(|f| f.path()) {
+                    self.add_tree(path);
+                }
+            }
+            gio::FileMonitorEvent::ChangesDoneHint => {}
+            _ => {
+                // Ignore changes that are still in progress, attribute changes,
+                // and unmounting, which is handled by checking library availability.
+                return;
+            }
+        }
+
+        self.schedule_change();
+    }
+
+    /// Report a change once the library has been quiet for a while.
+    fn schedule_change(&self) {
+        if let Some(source_id) = self.inner.pending.take() {
+            source_id.remove();
+        }
+
+        let weak = Rc::downgrade(&self.inner);
+        let source_id = glib::timeout_add_seconds_local_once(SETTLE_SECONDS, move || {
+            if let Some(inner) = weak.upgrade() {
+                inner.pending.take();
+                info!("Library has changed");
+                (inner.on_change)();
+            }
+        });
+
+        self.inner.pending.replace(Some(source_id));
+    }
+}
+
+/// Find a directory and all directories inside it.
+/// Returns nothing if the path isn't a directory.
+fn find_dirs(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs = vec![];
+    let mut to_visit = vec![dir.to_path_buf()];
+
+    while let Some(dir) = to_visit.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+
+        to_visit.extend(
+            entries
+                .flatten()
+                .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+                .map(|entry| entry.path()),
+        );
+
+        dirs.push(dir);
+    }
+
+    dirs
+}
@@ -0,0 +1,206 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// Access to the user's pictures via XDG desktop portals.
+//
+// Inside Flatpak, Fotema has no access to the host file system. The user grants
+// access to a pictures directory with the FileChooser portal, which exports the
+// directory through the Documents portal at a path like `/run/user/1000/doc/abc123/Pictures`.
+// All file access (scanning, previewing, thumbnailing) goes through that
+// portal path. The document ID is persisted so the portal path can be found again
+// on later launches, and the host path is kept for display to the user.
+
+use ashpd::{
+    desktop::{background::Background, file_chooser::OpenFileRequest, open_uri},
+    documents::{DocumentID, Documents},
+    WindowIdentifier,
+};
+
+use relm4::gtk;
+use relm4::gtk::prelude::*;
+
+use crate::fl;
+
+use regex::Regex;
+
+use tracing::{debug, error, info, warn};
+
+use std::path::{Path, PathBuf};
+
+/// A directory the user has granted access to.
+#[derive(Debug, Clone)]
+pub struct GrantedDirectory {
+    /// Path to use for file access. A Documents portal path if running in Flatpak.
+    pub path: PathBuf,
+
+    /// Documents portal ID if directory was exported by the Documents portal.
+    pub doc_id: Option<String>,
+
+    /// Path to directory on host file system. Only for display purposes because
+    /// the directory is probably not accessible via this path.
+    pub host_path: Option<PathBuf>,
+}
+
+/// Show the FileChooser portal so the user can choose a directory.
+/// Returns None if the user cancelled the chooser.
+pub async fn choose_directory(widget: &impl IsA<gtk::Widget>) -> Option<GrantedDirectory> {
+    let root = widget.root()?;
+
+    let identifier = WindowIdentifier::from_native(&root).await;
+    let request = OpenFileRequest::default()
+        .directory(true)
+        .identifier(identifier)
+        .modal(true) // can't be modal without identifier.
+        .multiple(false);
+
+    let files = match request.send().await.and_then(|r| r.response()) {
+        Ok(files) => files,
+        Err(err) => {
+            error!("Failed to open a file: {err}");
+            return None;
+        }
+    };
+
+    info!("Open: {:?}", files);
+    let Some(path) = files.uris().first().and_then(|uri| uri.to_file_path().ok()) else {
+        error!("No directory!");
+        return None;
+    };
+
+    let doc_id = document_id(&path);
+    let host_path = match doc_id {
+        Some(ref doc_id) => host_path(doc_id).await,
+        None => None,
+    };
+
+    Some(GrantedDirectory {
+        path,
+        doc_id,
+        host_path,
+    })
+}
+
+/// Parse Document ID from a Documents portal path.
+pub fn document_id(path: &Path) -> Option<String> {
+    let re = Regex::new(r"^/run/user/[0-9]+/doc/([0-9a-fA-F]+)/").unwrap();
+    let doc_id = path
+        .to_str()
+        .and_then(|s| re.captures(s))
+        .and_then(|re_match| re_match.get(1))
+        .map(|doc_id_match| doc_id_match.as_str().to_string());
+    debug!("Document ID={:?}", doc_id);
+    doc_id
+}
+
+/// Look up the host path of a document exported by the Documents portal.
+pub async fn host_path(doc_id: &str) -> Option<PathBuf> {
+    let proxy = match Documents::new().await {
+        Ok(proxy) => proxy,
+        Err(e) => {
+            warn!("Documents portal not available: {}", e);
+            return None;
+        }
+    };
+
+    let doc_id = DocumentID::from(doc_id);
+    match proxy.host_paths(&[doc_id.clone()]).await {
+        Ok(paths) => {
+            let path = paths.get(&doc_id).map(|p| {
+                let p: &Path = p.as_ref();
+                p.to_path_buf()
+            });
+            info!("Host path for document {:?}: {:?}", doc_id, path);
+            path
+        }
+        Err(e) => {
+            error!("Failed getting host path for document {:?}: {}", doc_id, e);
+            None
+        }
+    }
+}
+
+/// Find the current portal path for a previously granted directory.
+/// The Documents portal mount point can move between sessions, but document
+/// IDs are stable, so rebuild the path from the current mount point.
+/// Outside of a sandbox there is no Documents portal path to restore.
+pub async fn restore(dir: &Path, doc_id: &str) -> Option<PathBuf> {
+    if !ashpd::is_sandboxed().await {
+        return None;
+    }
+
+    let proxy = Documents::new().await.ok()?;
+    let mount_point = match proxy.mount_point().await {
+        Ok(mount_point) => mount_point,
+        Err(e) => {
+            error!("Failed getting Documents portal mount point: {}", e);
+            return None;
+        }
+    };
+
+    let mount_point: &Path = mount_point.as_ref();
+    let path = mount_point.join(doc_id).join(dir.file_name()?);
+    info!("Restored portal path {:?} for {:?}", path, dir);
+
+    path.is_dir().then_some(path)
+}
+
+/// Ask the Background portal for permission to keep running without a window,
+/// and to be started in the background on login.
+/// Returns true if the user allowed running in the background.
+pub async fn request_background(widget: &impl IsA<gtk::Widget>, enable: bool) -> bool {
+    let identifier = match widget.root() {
+        Some(root) => WindowIdentifier::from_native(&root).await,
+        None => None,
+    };
+
+    let reason = fl!("prefs-library-section-run-in-background", "reason");
+
+    // Disabling autostart also goes through the portal so it can remove the autostart entry.
+    let request = Background::request()
+        .identifier(identifier)
+        .reason(reason.as_str())
+        .auto_start(enable)
+        .command(["fotema", "--background"])
+        .dbus_activatable(false);
+
+    match request.send().await.and_then(|r| r.response()) {
+        Ok(response) => {
+            info!(
+                "Background portal: run in background={}, autostart={}",
+                response.run_in_background(),
+                response.auto_start()
+            );
+            enable && response.run_in_background()
+        }
+        Err(e) => {
+            error!("Failed requesting background permission: {}", e);
+            false
+        }
+    }
+}
+
+/// Offer a file to another app, such as a messaging or video editing app,
+/// with the OpenURI portal's app chooser.
+pub async fn share_file(widget: &impl IsA<gtk::Widget>, path: &Path) {
+    let identifier = match widget.root() {
+        Some(root) => WindowIdentifier::from_native(&root).await,
+        None => None,
+    };
+
+    let file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) => {
+            error!("Failed opening {:?} for sharing: {}", path, e);
+            return;
+        }
+    };
+
+    let request = open_uri::OpenFileRequest::default()
+        .identifier(identifier)
+        .ask(true);
+
+    if let Err(e) = request.send_file(&file).await {
+        error!("Failed sharing {:?}: {}", path, e);
+    }
+}
@@ -12,18 +12,21 @@ use app::App;
 
 use config::{APP_ID, GETTEXT_PACKAGE, LOCALEDIR, RESOURCES_FILE};
 use gettextrs::{gettext, LocaleCategory};
-use gtk::prelude::ApplicationExt;
+use gtk::prelude::{ApplicationExt, GtkApplicationExt, GtkWindowExt};
 use gtk::{gio, glib};
 use relm4::{
     actions::{AccelsPlus, RelmAction, RelmActionGroup},
     gtk, main_application, RelmApp,
 };
 
+use std::cell::Cell;
+
 use tracing_subscriber::filter::EnvFilter;
 use tracing_subscriber::filter::LevelFilter;
 
 relm4::new_action_group!(AppActionGroup, "app");
 relm4::new_stateless_action!(QuitAction, AppActionGroup, "quit");
+relm4::new_stateless_action!(ShowWindowAction, AppActionGroup, "show-window");
 
 fn main() {
     gtk::init().unwrap();
@@ -73,11 +76,43 @@ fn main() {
             app.quit();
         })
     };
+
+    // Activated from desktop notifications.
+    let show_window_action = {
+        let app = app.clone();
+        RelmAction::<ShowWindowAction>::new_stateless(move |_| {
+            if let Some(window) = app.windows().first() {
+                window.present();
+            } else {
+                app.activate();
+            }
+        })
+    };
+
     actions.add_action(quit_action);
+    actions.add_action(show_window_action);
     actions.register_for_main_application();
 
     app.set_accelerators_for_action::<QuitAction>(&["<Control>q"]);
 
+    // Started by autostart from the Background portal.
+    let start_in_background = std::env::args().any(|arg| arg == "--background");
+
+    // Launching Fotema again while it is running in the background activates the
+    // running instance, so bring back the hidden window. The first activation
+    // after autostart must leave the window hidden.
+    {
+        let is_first_activation = Cell::new(true);
+        app.connect_activate(move |app| {
+            if is_first_activation.replace(false) && start_in_background {
+                return;
+            }
+            if let Some(window) = app.windows().first() {
+                window.present();
+            }
+        });
+    }
+
     let app = RelmApp::from_app(app);
 
     let data = res
@@ -87,5 +122,6 @@ fn main() {
         )
         .unwrap();
     relm4::set_global_css(&glib::GString::from_utf8_checked(data.to_vec()).unwrap());
-    app.visible_on_activate(false).run::<App>(());
+
+    app.visible_on_activate(false).run::<App>(start_in_background);
 }